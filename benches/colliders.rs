@@ -0,0 +1,227 @@
+//! Compares world colliders built from greedy-merged voxel boxes with a triangle mesh of the
+//! rendered voxel faces: build time, shape count, and how steadily the player walks across each.
+//! `trimesh` is built the way the scene collider used to be, without trimesh flags;
+//! `trimesh-fix` adds `FIX_INTERNAL_EDGES` to show what fixing the internal edges would cost.
+//!
+//! Run with `cargo bench --bench colliders`.
+
+use std::time::{Duration, Instant};
+
+use bevy::{asset::AssetPlugin, ecs::system::RunSystemOnce, prelude::*, time::TimeUpdateStrategy};
+use bevy_rapier3d::prelude::*;
+use voxel_sampo::{
+    ability::AbilityPlugin,
+    action::ActionPlugin,
+    camera::CameraAngle,
+    config::{Config, PHYSICS_TICK_RATE},
+    entities::player::spawn_player,
+    physics::PhysicsPlugin,
+    platform::PlatformPlugin,
+    player::{systems::movement_direction, GroundedState, Player, PlayerPlugin},
+    voxel::{systems::box_collider, VoxelGrid, VoxelPlugin},
+    world::components::Level,
+};
+
+const VOXEL_SIZE: f32 = 0.05;
+const BUILD_RUNS: u32 = 5;
+const WALK_TICKS: usize = 150;
+
+/// A 192 x 48 x 192 room: a two voxel thick checkered floor, walls, pillars and a staircase.
+fn test_room() -> VoxelGrid {
+    let mut voxels = Vec::new();
+    for x in -96..96 {
+        for z in -96..96 {
+            let checker = if (x / 4 + z / 4) % 2 == 0 { 1 } else { 2 };
+            voxels.push((IVec3::new(x, -1, z), checker));
+            voxels.push((IVec3::new(x, -2, z), 3));
+            let wall = x.abs() >= 94 || z.abs() >= 94;
+            let pillar = (x.abs() - 60).abs() < 3 && (z.abs() - 60).abs() < 3;
+            if wall || pillar {
+                for y in 0..48 {
+                    voxels.push((IVec3::new(x, y, z), 4));
+                }
+            }
+            // Stairs one voxel high and four deep rising towards +x in one corner.
+            if (40..90).contains(&x) && (-90..-70).contains(&z) {
+                for y in 0..(x - 40) / 4 {
+                    voxels.push((IVec3::new(x, y, z), 5));
+                }
+            }
+        }
+    }
+    VoxelGrid::from_voxels(voxels)
+}
+
+/// Two triangles per exposed voxel face, with separate vertices per face like a rendered mesh.
+fn face_mesh(grid: &VoxelGrid) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    for (cell, _) in grid.iter() {
+        for normal in [
+            IVec3::X,
+            IVec3::NEG_X,
+            IVec3::Y,
+            IVec3::NEG_Y,
+            IVec3::Z,
+            IVec3::NEG_Z,
+        ] {
+            if grid.get(cell + normal).is_some() {
+                continue;
+            }
+            let normal = normal.as_vec3();
+            let center = cell.as_vec3() + Vec3::splat(0.5) + normal * 0.5;
+            let (u, v) = normal.any_orthonormal_pair();
+            let (u, v) = if u.cross(v).dot(normal) > 0.0 {
+                (u, v)
+            } else {
+                (v, u)
+            };
+            let base = vertices.len() as u32;
+            vertices.extend([
+                center + (-u - v) * 0.5,
+                center + (u - v) * 0.5,
+                center + (u + v) * 0.5,
+                center + (-u + v) * 0.5,
+            ]);
+            indices.extend([[base, base + 1, base + 2], [base, base + 2, base + 3]]);
+        }
+    }
+    (vertices, indices)
+}
+
+fn time_build(build: impl Fn() -> Collider) -> (Collider, Duration) {
+    let mut collider = build();
+    let start = Instant::now();
+    for _ in 0..BUILD_RUNS {
+        collider = build();
+    }
+    (collider, start.elapsed() / BUILD_RUNS)
+}
+
+#[derive(Default)]
+struct WalkStats {
+    airborne_ticks: usize,
+    max_vertical_speed: f32,
+    max_normal_tilt: f32,
+    distance: f32,
+}
+
+/// Walks the player forward across the floor and records how often the ground contact wavers.
+fn walk(collider: Collider) -> WalkStats {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        AssetPlugin::default(),
+        RapierPhysicsPlugin::<NoUserData>::default().in_fixed_schedule(),
+        ActionPlugin,
+        PlayerPlugin,
+        AbilityPlugin,
+        PhysicsPlugin,
+        PlatformPlugin,
+        VoxelPlugin,
+    ))
+    .init_asset::<StandardMaterial>()
+    .init_resource::<ButtonInput<KeyCode>>()
+    .init_resource::<ButtonInput<MouseButton>>()
+    .insert_resource(Config::default())
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        1.0 / PHYSICS_TICK_RATE,
+    )));
+    app.finish();
+    app.cleanup();
+    app.world_mut().spawn(CameraAngle::default());
+    app.update();
+
+    app.world_mut().spawn((
+        Transform::from_scale(Vec3::splat(VOXEL_SIZE)),
+        RigidBody::Fixed,
+        collider,
+    ));
+    let forward = movement_direction(Vec2::Y, Some(&CameraAngle::default()), true);
+    let start = -forward * 3.0 + Vec3::Y * 0.3;
+    app.world_mut()
+        .run_system_once(
+            move |mut commands: Commands,
+                  mut meshes: ResMut<Assets<Mesh>>,
+                  mut materials: ResMut<Assets<StandardMaterial>>,
+                  config: Res<Config>| {
+                let level = Level {
+                    spawn_position: start,
+                    ..default()
+                };
+                spawn_player(&mut commands, &mut meshes, &mut materials, &config, &level);
+            },
+        )
+        .expect("spawn_player should run");
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let mut player = app
+        .world_mut()
+        .query_filtered::<(&GroundedState, &Velocity, &Transform), With<Player>>();
+    let mut stats = WalkStats::default();
+    let from = player.single(app.world()).expect("player").2.translation;
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::KeyW);
+    for tick in 0..WALK_TICKS {
+        app.update();
+        // Skip the acceleration from standstill.
+        if tick < 10 {
+            continue;
+        }
+        let (grounded_state, velocity, _) = player.single(app.world()).expect("player");
+        if !grounded_state.is_grounded {
+            stats.airborne_ticks += 1;
+        }
+        stats.max_vertical_speed = stats.max_vertical_speed.max(velocity.linvel.y.abs());
+        stats.max_normal_tilt = stats.max_normal_tilt.max(
+            grounded_state
+                .ground_normal
+                .angle_between(Vec3::Y)
+                .to_degrees(),
+        );
+    }
+    let to = player.single(app.world()).expect("player").2.translation;
+    stats.distance = (to - from).dot(forward);
+    stats
+}
+
+fn report(name: &str, shapes: usize, build_time: Duration, stats: &WalkStats) {
+    println!(
+        "{name:<12} {shapes:>8} {:>10.2} {:>9}/{} {:>10.3} {:>9.2} {:>9.2}",
+        build_time.as_secs_f64() * 1000.0,
+        stats.airborne_ticks,
+        WALK_TICKS - 10,
+        stats.max_vertical_speed,
+        stats.max_normal_tilt,
+        stats.distance,
+    );
+}
+
+fn main() {
+    let grid = test_room();
+    println!("room: {} voxels", grid.iter().count());
+    println!(
+        "{:<12} {:>8} {:>10} {:>11} {:>10} {:>9} {:>9}",
+        "collider", "shapes", "build ms", "airborne", "max |vy|", "tilt deg", "walked m"
+    );
+
+    let (boxes, box_time) = time_build(|| box_collider(&grid.greedy_boxes(|_| ())));
+    let box_count = grid.greedy_boxes(|_| ()).len();
+    report("boxes", box_count, box_time, &walk(boxes));
+
+    let (vertices, indices) = face_mesh(&grid);
+    for (name, flags) in [
+        ("trimesh", TriMeshFlags::empty()),
+        ("trimesh-fix", TriMeshFlags::FIX_INTERNAL_EDGES),
+    ] {
+        let (trimesh, trimesh_time) = time_build(|| {
+            Collider::trimesh_with_flags(vertices.clone(), indices.clone(), flags)
+                .expect("valid face mesh")
+        });
+        report(name, indices.len(), trimesh_time, &walk(trimesh));
+    }
+}
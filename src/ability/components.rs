@@ -0,0 +1,158 @@
+use bevy::{ecs::system::EntityCommands, prelude::*};
+
+use crate::config::{AbilityConfig, DashConfig, DoubleJumpConfig, WallJumpConfig};
+
+/// Uses of an ability, refilled on landing and spaced out by a cooldown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbilityCharges {
+    pub max: u32,
+    pub available: u32,
+    /// Seconds after a use before the ability can be used again.
+    pub cooldown: f32,
+    pub cooldown_remaining: f32,
+}
+
+impl AbilityCharges {
+    pub fn new(max: u32, cooldown: f32) -> Self {
+        Self {
+            max,
+            available: max,
+            cooldown,
+            cooldown_remaining: 0.0,
+        }
+    }
+
+    pub fn ready(&self) -> bool {
+        self.available > 0 && self.cooldown_remaining <= 0.0
+    }
+
+    /// Uses one charge if the ability is ready.
+    pub fn try_use(&mut self) -> bool {
+        if !self.ready() {
+            return false;
+        }
+        self.available -= 1;
+        self.cooldown_remaining = self.cooldown;
+        true
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        self.cooldown_remaining = (self.cooldown_remaining - dt).max(0.0);
+    }
+
+    pub fn refill(&mut self) {
+        self.available = self.max;
+    }
+}
+
+/// Extra jumps in mid-air, as high as a jump from the ground.
+#[derive(Component, Debug, Clone)]
+pub struct DoubleJump {
+    pub charges: AbilityCharges,
+}
+
+impl From<&DoubleJumpConfig> for DoubleJump {
+    fn from(config: &DoubleJumpConfig) -> Self {
+        Self {
+            charges: AbilityCharges::new(config.charges, config.cooldown),
+        }
+    }
+}
+
+impl Default for DoubleJump {
+    fn default() -> Self {
+        Self::from(&DoubleJumpConfig::default())
+    }
+}
+
+/// Jump off a wall the player is pressed against while airborne.
+#[derive(Component, Debug, Clone)]
+#[require(WallContact)]
+pub struct WallJump {
+    pub charges: AbilityCharges,
+    /// Horizontal speed away from the wall; the player's `max_speed` still caps it.
+    pub push_speed: f32,
+}
+
+impl From<&WallJumpConfig> for WallJump {
+    fn from(config: &WallJumpConfig) -> Self {
+        Self {
+            charges: AbilityCharges::new(config.charges, config.cooldown),
+            push_speed: config.push_speed,
+        }
+    }
+}
+
+impl Default for WallJump {
+    fn default() -> Self {
+        Self::from(&WallJumpConfig::default())
+    }
+}
+
+/// Short burst of horizontal speed that ignores gravity and the walking speed cap.
+#[derive(Component, Debug, Clone)]
+pub struct Dash {
+    pub charges: AbilityCharges,
+    pub speed: f32,
+    /// Seconds the dash lasts.
+    pub duration: f32,
+    /// Direction and seconds left of the dash in progress.
+    pub active: Option<(Vec3, f32)>,
+}
+
+impl From<&DashConfig> for Dash {
+    fn from(config: &DashConfig) -> Self {
+        Self {
+            charges: AbilityCharges::new(config.charges, config.cooldown),
+            speed: config.speed,
+            duration: config.duration,
+            active: None,
+        }
+    }
+}
+
+impl Default for Dash {
+    fn default() -> Self {
+        Self::from(&DashConfig::default())
+    }
+}
+
+impl Dash {
+    pub fn is_dashing(&self) -> bool {
+        self.active.is_some()
+    }
+}
+
+/// Outward normal of a wall right next to the player, from short horizontal rays.
+#[derive(Component, Debug, Clone, Default)]
+pub struct WallContact {
+    pub normal: Option<Vec3>,
+}
+
+/// Gives the player the abilities that differ between `previous` and `abilities`, inserting
+/// fresh ones and removing those left out. Abilities whose config didn't change, including any
+/// inserted from code, are left as they are.
+pub fn grant_abilities(
+    player: &mut EntityCommands,
+    abilities: &AbilityConfig,
+    previous: &AbilityConfig,
+) {
+    if abilities.double_jump != previous.double_jump {
+        match &abilities.double_jump {
+            Some(config) => player.insert(DoubleJump::from(config)),
+            None => player.remove::<DoubleJump>(),
+        };
+    }
+    if abilities.wall_jump != previous.wall_jump {
+        match &abilities.wall_jump {
+            Some(config) => player.insert(WallJump::from(config)),
+            None => player.remove::<WallJump>(),
+        };
+    }
+    if abilities.dash != previous.dash {
+        match &abilities.dash {
+            Some(config) => player.insert(Dash::from(config)),
+            None => player.remove::<Dash>(),
+        };
+    }
+}
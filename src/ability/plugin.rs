@@ -0,0 +1,33 @@
+use super::systems::{
+    ability_recharge_system, air_jump_system, dash_system, wall_detection_system,
+};
+use crate::physics::systems::ground_detection_system;
+use crate::player::systems::player_input_system;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Double jump, wall jump and dash. Each ability works once its component is on the player,
+/// so levels can grant or take them away at runtime.
+#[derive(Default)]
+pub struct AbilityPlugin;
+
+impl Plugin for AbilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                (
+                    wall_detection_system,
+                    ability_recharge_system,
+                    air_jump_system,
+                )
+                    .chain()
+                    .after(ground_detection_system)
+                    .before(player_input_system),
+                dash_system
+                    .after(player_input_system)
+                    .before(PhysicsSet::SyncBackend),
+            ),
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use super::components::{Dash, DoubleJump, WallContact, WallJump};
+use crate::camera::CameraAngle;
+use crate::player::systems::movement_direction;
+use crate::player::{GroundedState, JumpTimers, Player, PlayerInput};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+const WALL_RAY_OFFSET_EPS: f32 = 2e-2;
+const WALL_RAY_DISTANCE: f32 = 6e-2;
+
+const WALL_RAY_DIRECTIONS: [Vec3; 4] = [Vec3::X, Vec3::NEG_X, Vec3::Z, Vec3::NEG_Z];
+
+pub fn wall_detection_system(
+    mut query: Query<(Entity, &mut WallContact, &Transform, &Collider), With<Player>>,
+    rapier_context: ReadRapierContext,
+) {
+    let Ok(context) = rapier_context.single() else {
+        return;
+    };
+    for (entity, mut wall_contact, transform, collider) in query.iter_mut() {
+        let half_extents = collider
+            .as_cuboid()
+            .map(|cuboid| cuboid.half_extents())
+            .unwrap_or(Vec3::splat(0.25));
+        let filter = QueryFilter::default().exclude_collider(entity);
+
+        // Rays start just inside each side face so touching walls are still hit.
+        wall_contact.normal = WALL_RAY_DIRECTIONS.into_iter().find_map(|direction| {
+            let inset = (half_extents * direction).length() - WALL_RAY_OFFSET_EPS;
+            let origin = transform.translation + direction * inset;
+            context
+                .cast_ray(origin, direction, WALL_RAY_DISTANCE, true, filter)
+                .map(|_| -direction)
+        });
+    }
+}
+
+type RechargeQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static GroundedState,
+        &'static JumpTimers,
+        Option<&'static mut DoubleJump>,
+        Option<&'static mut WallJump>,
+        Option<&'static mut Dash>,
+    ),
+    With<Player>,
+>;
+
+/// Cools abilities down every tick and refills their charges while standing on the ground.
+pub fn ability_recharge_system(time: Res<Time>, mut query: RechargeQuery) {
+    let dt = time.delta_secs();
+    for (grounded_state, jump_timers, double_jump, wall_jump, dash) in query.iter_mut() {
+        let on_ground = grounded_state.is_grounded && !jump_timers.jumping;
+        let charges = [
+            double_jump.map(|ability| ability.map_unchanged(|ability| &mut ability.charges)),
+            wall_jump.map(|ability| ability.map_unchanged(|ability| &mut ability.charges)),
+            dash.map(|ability| ability.map_unchanged(|ability| &mut ability.charges)),
+        ];
+        for mut charges in charges.into_iter().flatten() {
+            if charges.cooldown_remaining > 0.0 {
+                charges.tick(dt);
+            }
+            if on_ground && charges.available < charges.max {
+                charges.refill();
+            }
+        }
+    }
+}
+
+type AirJumpQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Player,
+        &'static mut PlayerInput,
+        &'static mut JumpTimers,
+        &'static GroundedState,
+        &'static mut Velocity,
+        Option<&'static mut DoubleJump>,
+        Option<(&'static mut WallJump, &'static WallContact)>,
+    ),
+>;
+
+/// Spends a jump press that can't leave the ground on a wall jump or, failing that, a double jump.
+pub fn air_jump_system(time: Res<Time>, mut query: AirJumpQuery) {
+    let dt = time.delta_secs();
+    for (
+        player,
+        mut player_input,
+        mut jump_timers,
+        grounded_state,
+        mut velocity,
+        double_jump,
+        wall_jump,
+    ) in query.iter_mut()
+    {
+        if !player_input.jump_requested || jump_timers.can_ground_jump(grounded_state) {
+            continue;
+        }
+        let launch_speed = player.jump_launch_speed(dt);
+
+        let wall_normal = wall_jump.and_then(|(mut wall_jump, wall_contact)| {
+            let normal = wall_contact.normal?;
+            wall_jump
+                .charges
+                .try_use()
+                .then(|| normal * wall_jump.push_speed)
+        });
+        if let Some(push) = wall_normal {
+            velocity.linvel = Vec3::new(push.x, launch_speed, push.z);
+        } else if double_jump.is_some_and(|mut double_jump| double_jump.charges.try_use()) {
+            velocity.linvel.y = launch_speed;
+        } else {
+            continue;
+        }
+
+        jump_timers.start_jump();
+        player_input.jump_requested = false;
+    }
+}
+
+type DashQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Player,
+        &'static mut PlayerInput,
+        Option<&'static mut Dash>,
+        &'static mut Velocity,
+        &'static mut GravityScale,
+        &'static mut Damping,
+        &'static mut ExternalForce,
+    ),
+>;
+
+/// Starts a dash on request and holds the player at dash speed until it ends.
+pub fn dash_system(time: Res<Time>, mut query: DashQuery, camera_query: Query<&CameraAngle>) {
+    let dt = time.delta_secs();
+    for (
+        player,
+        mut player_input,
+        dash,
+        mut velocity,
+        mut gravity_scale,
+        mut damping,
+        mut external_force,
+    ) in query.iter_mut()
+    {
+        // Consume the press even without the ability so it doesn't fire once unlocked.
+        let requested = std::mem::take(&mut player_input.dash_requested);
+        let Some(mut dash) = dash else {
+            continue;
+        };
+
+        if requested && !dash.is_dashing() {
+            let mut direction = movement_direction(
+                player_input.movement,
+                camera_query.single().ok(),
+                player.snap_to_axis,
+            );
+            if direction == Vec3::ZERO {
+                direction =
+                    Vec3::new(velocity.linvel.x, 0.0, velocity.linvel.z).normalize_or_zero();
+            }
+            if direction != Vec3::ZERO && dash.charges.try_use() {
+                dash.active = Some((direction, dash.duration));
+            }
+        }
+
+        let Some((direction, remaining)) = dash.active else {
+            continue;
+        };
+        velocity.linvel = direction * dash.speed;
+        gravity_scale.0 = 0.0;
+        damping.linear_damping = 0.0;
+        external_force.force = Vec3::ZERO;
+        let remaining = remaining - dt;
+        dash.active = (remaining > 0.0).then_some((direction, remaining));
+    }
+}
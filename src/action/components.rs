@@ -0,0 +1,201 @@
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Something the player can do, independent of the device that triggers it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveForward,
+    MoveBack,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Dash,
+    RotateCameraClockwise,
+    RotateCameraCounterClockwise,
+    OrbitCamera,
+    CycleCameraMode,
+    ZoomIn,
+    ZoomOut,
+    ToggleDebug,
+    ToggleSettings,
+}
+
+impl Action {
+    pub const ALL: [Action; 14] = [
+        Action::MoveForward,
+        Action::MoveBack,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Jump,
+        Action::Dash,
+        Action::RotateCameraClockwise,
+        Action::RotateCameraCounterClockwise,
+        Action::OrbitCamera,
+        Action::CycleCameraMode,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ToggleDebug,
+        Action::ToggleSettings,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveForward => "Move forward",
+            Action::MoveBack => "Move back",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Jump => "Jump",
+            Action::Dash => "Dash",
+            Action::RotateCameraClockwise => "Rotate camera clockwise",
+            Action::RotateCameraCounterClockwise => "Rotate camera counter-clockwise",
+            Action::OrbitCamera => "Orbit camera (hold and drag)",
+            Action::CycleCameraMode => "Switch camera mode",
+            Action::ZoomIn => "Zoom in",
+            Action::ZoomOut => "Zoom out",
+            Action::ToggleDebug => "Toggle debug overlay",
+            Action::ToggleSettings => "Open settings",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AxisDirection {
+    Positive,
+    Negative,
+}
+
+impl AxisDirection {
+    pub fn sign(self) -> f32 {
+        match self {
+            AxisDirection::Positive => 1.0,
+            AxisDirection::Negative => -1.0,
+        }
+    }
+}
+
+/// A physical input that can trigger an [`Action`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    GamepadButton(GamepadButton),
+    /// One half of a stick axis; the value grows with deflection past the dead zone.
+    GamepadAxis(GamepadAxis, AxisDirection),
+}
+
+/// Kind of device a [`Binding`] belongs to; rebinding replaces bindings of the same kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BindingDevice {
+    KeyboardMouse,
+    Gamepad,
+}
+
+impl Binding {
+    pub fn device(self) -> BindingDevice {
+        match self {
+            Binding::Key(_) | Binding::Mouse(_) => BindingDevice::KeyboardMouse,
+            Binding::GamepadButton(_) | Binding::GamepadAxis(..) => BindingDevice::Gamepad,
+        }
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{key:?}"),
+            Binding::Mouse(button) => write!(f, "Mouse {button:?}"),
+            Binding::GamepadButton(button) => write!(f, "Pad {button:?}"),
+            Binding::GamepadAxis(axis, AxisDirection::Positive) => write!(f, "Pad {axis:?}+"),
+            Binding::GamepadAxis(axis, AxisDirection::Negative) => write!(f, "Pad {axis:?}-"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ActionData {
+    /// Strength from 0 to 1; digital inputs are either 0 or 1.
+    pub value: f32,
+    pub pressed: bool,
+    pub just_pressed: bool,
+    pub just_released: bool,
+    pressed_last_frame: bool,
+}
+
+/// Per-frame state of every [`Action`], merged across all of its bindings.
+///
+/// Systems that drive the player without a device, such as AI, can write to it after
+/// [`ActionSystems`](super::ActionSystems).
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ActionState {
+    actions: [ActionData; Action::ALL.len()],
+    zoom: f32,
+}
+
+impl ActionState {
+    pub fn get(&self, action: Action) -> &ActionData {
+        &self.actions[action as usize]
+    }
+
+    pub fn value(&self, action: Action) -> f32 {
+        self.get(action).value
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        self.get(action).pressed
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.get(action).just_pressed
+    }
+
+    pub fn just_released(&self, action: Action) -> bool {
+        self.get(action).just_released
+    }
+
+    /// Camera-relative movement: `x` is right, `y` is forward.
+    pub fn movement(&self) -> Vec2 {
+        Vec2::new(
+            self.value(Action::MoveRight) - self.value(Action::MoveLeft),
+            self.value(Action::MoveForward) - self.value(Action::MoveBack),
+        )
+    }
+
+    /// Zoom from the scroll wheel and pinch gestures this frame, as the natural log of the factor
+    /// to scale the viewport by; negative zooms in.
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom;
+    }
+
+    /// Overrides this frame's value of `action`, keeping the press transitions consistent.
+    pub fn set_value(&mut self, action: Action, value: f32) {
+        let data = &mut self.actions[action as usize];
+        data.value = value.clamp(0.0, 1.0);
+        data.pressed = data.value > 0.0;
+        data.just_pressed = data.pressed && !data.pressed_last_frame;
+        data.just_released = !data.pressed && data.pressed_last_frame;
+    }
+
+    pub fn press(&mut self, action: Action) {
+        self.set_value(action, 1.0);
+    }
+
+    pub fn release(&mut self, action: Action) {
+        self.set_value(action, 0.0);
+    }
+
+    /// Starts a new frame for `action` with the merged value of its bindings.
+    ///
+    /// `tapped` catches a binding pressed and released again within the frame.
+    pub fn update(&mut self, action: Action, value: f32, tapped: bool) {
+        let data = &mut self.actions[action as usize];
+        data.pressed_last_frame = data.pressed;
+        self.set_value(action, value);
+        self.actions[action as usize].just_pressed |= tapped;
+    }
+}
@@ -0,0 +1,30 @@
+use bevy::{
+    input::{gestures::PinchGesture, InputSystem},
+    prelude::*,
+};
+
+use super::components::ActionState;
+use super::systems::update_action_state_system;
+use crate::config::Config;
+
+/// Runs after device input is read and before anything consumes [`ActionState`].
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ActionSystems;
+
+/// Maps keyboard, mouse, gesture and gamepad input to [`ActionState`] every frame.
+#[derive(Default)]
+pub struct ActionPlugin;
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .init_resource::<ActionState>()
+            .add_event::<PinchGesture>()
+            .add_systems(
+                PreUpdate,
+                update_action_state_system
+                    .in_set(ActionSystems)
+                    .after(InputSystem),
+            );
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::{
+    input::{
+        gestures::PinchGesture,
+        mouse::{AccumulatedMouseScroll, MouseScrollUnit},
+    },
+    prelude::*,
+};
+
+use super::components::{Action, ActionState, Binding};
+use crate::config::Config;
+
+/// Pixels of smooth (touchpad) scrolling that count as one mouse wheel notch.
+const PIXELS_PER_SCROLL_NOTCH: f32 = 100.0;
+
+pub fn update_action_state_system(
+    keyboard_input: Option<Res<ButtonInput<KeyCode>>>,
+    mouse_input: Option<Res<ButtonInput<MouseButton>>>,
+    mouse_scroll: Option<Res<AccumulatedMouseScroll>>,
+    mut pinch_gestures: EventReader<PinchGesture>,
+    gamepads: Query<&Gamepad>,
+    config: Res<Config>,
+    mut action_state: ResMut<ActionState>,
+) {
+    let mut zoom = 0.0;
+    if let Some(mouse_scroll) = mouse_scroll {
+        let notches = match mouse_scroll.unit {
+            MouseScrollUnit::Line => mouse_scroll.delta.y,
+            MouseScrollUnit::Pixel => mouse_scroll.delta.y / PIXELS_PER_SCROLL_NOTCH,
+        };
+        // Scrolling up zooms in.
+        zoom -= notches * config.camera.zoom_step.ln();
+    }
+    for pinch in pinch_gestures.read() {
+        // Spreading the fingers apart (a positive delta) zooms in.
+        zoom -= pinch.0;
+    }
+    action_state.set_zoom(zoom);
+
+    let bindings = &config.bindings;
+    for action in Action::ALL {
+        let mut value: f32 = 0.0;
+        let mut tapped = false;
+
+        for &binding in bindings.get(action) {
+            match binding {
+                Binding::Key(key) => {
+                    if let Some(keyboard_input) = &keyboard_input {
+                        if keyboard_input.pressed(key) {
+                            value = 1.0;
+                        }
+                        tapped |= keyboard_input.just_pressed(key);
+                    }
+                }
+                Binding::Mouse(button) => {
+                    if let Some(mouse_input) = &mouse_input {
+                        if mouse_input.pressed(button) {
+                            value = 1.0;
+                        }
+                        tapped |= mouse_input.just_pressed(button);
+                    }
+                }
+                Binding::GamepadButton(button) => {
+                    for gamepad in gamepads.iter() {
+                        if gamepad.pressed(button) {
+                            value = 1.0;
+                        }
+                        tapped |= gamepad.just_pressed(button);
+                    }
+                }
+                Binding::GamepadAxis(axis, direction) => {
+                    for gamepad in gamepads.iter() {
+                        let deflection = axis_deflection(gamepad, axis, bindings.stick_dead_zone);
+                        value = value.max(deflection * direction.sign());
+                    }
+                }
+            }
+        }
+
+        action_state.update(action, value, tapped);
+    }
+}
+
+/// The other axis of the stick `axis` belongs to, if it is a stick axis.
+fn stick_partner(axis: GamepadAxis) -> Option<GamepadAxis> {
+    match axis {
+        GamepadAxis::LeftStickX => Some(GamepadAxis::LeftStickY),
+        GamepadAxis::LeftStickY => Some(GamepadAxis::LeftStickX),
+        GamepadAxis::RightStickX => Some(GamepadAxis::RightStickY),
+        GamepadAxis::RightStickY => Some(GamepadAxis::RightStickX),
+        _ => None,
+    }
+}
+
+/// Deflection of `axis` with the dead zone applied to the whole stick, so a diagonal that is
+/// past the dead zone isn't cut off because neither axis is on its own.
+fn axis_deflection(gamepad: &Gamepad, axis: GamepadAxis, dead_zone: f32) -> f32 {
+    let deflection = gamepad.get(axis).unwrap_or(0.0);
+    let other = stick_partner(axis).and_then(|partner| gamepad.get(partner));
+    let stick = Vec2::new(deflection, other.unwrap_or(0.0));
+    apply_dead_zone(stick, dead_zone).x
+}
+
+/// Zeroes sticks inside `dead_zone` and rescales the rest so movement starts from 0 at its edge.
+fn apply_dead_zone(stick: Vec2, dead_zone: f32) -> Vec2 {
+    let deflection = stick.length().min(1.0);
+    if deflection <= dead_zone {
+        return Vec2::ZERO;
+    }
+    stick.normalize() * (deflection - dead_zone) / (1.0 - dead_zone)
+}
@@ -1,5 +1,39 @@
-use crate::config::{CameraConfig, BASE_ZOOM, CAMERA_ANGLES, ZOOM_OUT_VALUE};
-use bevy::prelude::*;
+use crate::config::CameraConfig;
+use crate::world::components::DEFAULT_LEVEL_PATH;
+use bevy::{prelude::*, render::camera::ScalingMode};
+use serde::{Deserialize, Serialize};
+
+/// Field of view in degrees standing in for an orthographic projection while blending it with
+/// a perspective one; narrow enough that swapping the two is not noticeable.
+pub const ORTHOGRAPHIC_BLEND_FOV: f32 = 1.0;
+
+/// How the camera frames the player.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CameraMode {
+    /// Orthographic, rotating between snap angles.
+    #[default]
+    Snapped,
+    /// Orthographic, orbited freely by dragging the mouse.
+    FreeOrbit,
+    /// Perspective from behind the player, orbited by dragging the mouse.
+    ThirdPerson,
+}
+
+impl CameraMode {
+    /// Mode that the switch action moves on to.
+    pub fn next(self) -> Self {
+        match self {
+            CameraMode::Snapped => CameraMode::FreeOrbit,
+            CameraMode::FreeOrbit => CameraMode::ThirdPerson,
+            CameraMode::ThirdPerson => CameraMode::Snapped,
+        }
+    }
+
+    /// Whether the camera ignores snap angles and can be orbited.
+    pub fn is_free(self) -> bool {
+        self != CameraMode::Snapped
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RotationDirection {
@@ -7,20 +41,63 @@ pub enum RotationDirection {
     CounterClockwise,
 }
 
+/// An angle the camera snaps to when rotated.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CameraStop {
+    /// Degrees around the player.
+    pub yaw: f32,
+    /// Degrees the camera looks down at the player from; `None` keeps `CameraConfig::height`.
+    #[serde(default)]
+    pub pitch: Option<f32>,
+}
+
+impl CameraStop {
+    pub fn new(yaw: f32) -> Self {
+        Self { yaw, pitch: None }
+    }
+}
+
+/// Index of the stop in `stops` closest to `angle`, going either way around.
+pub fn nearest_stop(stops: &[CameraStop], angle: f32) -> Option<usize> {
+    let distance = |stop: &CameraStop| {
+        let diff = (stop.yaw - angle).rem_euclid(360.0);
+        diff.min(360.0 - diff)
+    };
+    (0..stops.len()).min_by(|a, b| distance(&stops[*a]).total_cmp(&distance(&stops[*b])))
+}
+
 #[derive(Component, Debug, Clone)]
 pub struct CameraAngle {
     pub current_angle: f32,
+    /// Degrees the camera looks down at the player from.
+    pub pitch: f32,
 }
 
 impl Default for CameraAngle {
     fn default() -> Self {
-        Self {
-            current_angle: CAMERA_ANGLES[3],
-        }
+        Self::new(&CameraConfig::default(), DEFAULT_LEVEL_PATH)
     }
 }
 
 impl CameraAngle {
+    /// Starts at `camera_config.start_angle`, moved to the nearest snap angle of the level.
+    pub fn new(camera_config: &CameraConfig, scene_path: &str) -> Self {
+        let stops = camera_config.snap_angles_for(scene_path);
+        match nearest_stop(stops, camera_config.start_angle) {
+            Some(index) => Self {
+                current_angle: stops[index].yaw,
+                pitch: stops[index]
+                    .pitch
+                    .unwrap_or_else(|| camera_config.default_pitch()),
+            },
+            None => Self {
+                current_angle: camera_config.start_angle.rem_euclid(360.0),
+                pitch: camera_config.default_pitch(),
+            },
+        }
+    }
+
     pub fn get_camera_forward_direction(&self) -> Vec3 {
         let rad = self.current_angle.to_radians();
         Vec3::new(-rad.cos(), 0.0, -rad.sin()).normalize()
@@ -37,15 +114,216 @@ impl CameraAngle {
         camera_config: &CameraConfig,
     ) -> Transform {
         let rad = self.current_angle.to_radians();
+        let height = camera_config.distance * self.pitch.to_radians().tan();
         Transform::from_xyz(
             player_pos.x + camera_config.distance * rad.cos(),
-            player_pos.y + camera_config.height,
+            player_pos.y + height,
             player_pos.z + camera_config.distance * rad.sin(),
         )
         .looking_at(player_pos, Vec3::Y)
     }
 }
 
+/// Camera angles after the last two fixed ticks, which rotate the camera, used to smooth the
+/// rendered view between ticks.
+#[derive(Component, Debug, Clone)]
+pub struct CameraAngleInterpolation {
+    pub previous: CameraAngle,
+    pub current: CameraAngle,
+}
+
+impl CameraAngleInterpolation {
+    pub fn new(camera_angle: &CameraAngle) -> Self {
+        Self {
+            previous: camera_angle.clone(),
+            current: camera_angle.clone(),
+        }
+    }
+
+    /// Moves to `camera_angle` without interpolating from the old angle.
+    pub fn teleport(&mut self, camera_angle: &CameraAngle) {
+        *self = Self::new(camera_angle);
+    }
+
+    /// Angle `alpha` of the way from the previous tick to the current one, turning the shorter
+    /// way round.
+    pub fn interpolated(&self, alpha: f32) -> CameraAngle {
+        let from = &self.previous;
+        let to = &self.current;
+        let yaw_diff = (to.current_angle - from.current_angle + 180.0).rem_euclid(360.0) - 180.0;
+        CameraAngle {
+            current_angle: (from.current_angle + yaw_diff * alpha).rem_euclid(360.0),
+            pitch: from.pitch.lerp(to.pitch, alpha),
+        }
+    }
+}
+
+/// Where the camera looks at the player from and how much of the scene it takes in.
+///
+/// Views of different modes are blended during a mode switch; a perspective view with a narrow
+/// field of view far away stands in for an orthographic one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraView {
+    /// Degrees around the player, as in [`CameraAngle::current_angle`].
+    pub yaw: f32,
+    /// Degrees the camera looks down at the player from.
+    pub pitch: f32,
+    /// Height of the area in view at the player's distance.
+    pub view_height: f32,
+    /// Vertical field of view in degrees; `None` for an orthographic projection.
+    pub fov: Option<f32>,
+}
+
+impl CameraView {
+    /// View of `mode` with the camera at `camera_angle` and zoomed to `zoom_controller`.
+    pub fn new(
+        mode: CameraMode,
+        camera_angle: &CameraAngle,
+        zoom_controller: &CameraZoomController,
+        camera_config: &CameraConfig,
+    ) -> Self {
+        let (view_height, fov) = match mode {
+            CameraMode::Snapped | CameraMode::FreeOrbit => (zoom_controller.current_zoom, None),
+            CameraMode::ThirdPerson => {
+                let fov = camera_config.third_person_fov;
+                let height = 2.0 * camera_config.third_person_distance * half_tan(fov);
+                let zoom = zoom_controller.current_zoom / zoom_controller.base_zoom;
+                (height * zoom, Some(fov))
+            }
+        };
+        Self {
+            yaw: camera_angle.current_angle,
+            pitch: camera_angle.pitch,
+            view_height,
+            fov,
+        }
+    }
+
+    /// Blends towards `other`, turning the shorter way round; `t` goes from 0 to 1.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let yaw_diff = (other.yaw - self.yaw + 180.0).rem_euclid(360.0) - 180.0;
+        let fov = match (self.fov, other.fov) {
+            (None, None) => None,
+            (from, to) => Some(
+                from.unwrap_or(ORTHOGRAPHIC_BLEND_FOV)
+                    .lerp(to.unwrap_or(ORTHOGRAPHIC_BLEND_FOV), t),
+            ),
+        };
+        Self {
+            yaw: (self.yaw + yaw_diff * t).rem_euclid(360.0),
+            pitch: self.pitch.lerp(other.pitch, t),
+            view_height: self.view_height.lerp(other.view_height, t),
+            fov,
+        }
+    }
+
+    /// Distance from the player along the line of sight.
+    pub fn distance(&self, camera_config: &CameraConfig) -> f32 {
+        match self.fov {
+            Some(fov) => self.view_height / (2.0 * half_tan(fov)),
+            // Orthographic views keep `CameraConfig::distance` horizontally.
+            None => camera_config.distance / self.pitch.to_radians().cos(),
+        }
+    }
+
+    pub fn transform(&self, target: Vec3, camera_config: &CameraConfig) -> Transform {
+        let yaw = self.yaw.to_radians();
+        let pitch = self.pitch.to_radians();
+        let direction = Vec3::new(
+            yaw.cos() * pitch.cos(),
+            pitch.sin(),
+            yaw.sin() * pitch.cos(),
+        );
+        Transform::from_translation(target + direction * self.distance(camera_config))
+            .looking_at(target, Vec3::Y)
+    }
+
+    /// Half the size of the area in view along the world's horizontal axes, for a viewport
+    /// `aspect_ratio` times as wide as it is tall: its width lies along the camera's right and
+    /// its height along the direction it faces. `y` is 0, leaving the focus free to follow the
+    /// player between floors.
+    pub fn ground_half_extents(&self, aspect_ratio: f32) -> Vec3 {
+        let yaw = self.yaw.to_radians();
+        let facing = Vec3::new(yaw.cos().abs(), 0.0, yaw.sin().abs());
+        let across = Vec3::new(facing.z, 0.0, facing.x);
+        (facing * self.view_height + across * self.view_height * aspect_ratio) / 2.0
+    }
+
+    pub fn projection(&self, camera_config: &CameraConfig) -> Projection {
+        match self.fov {
+            Some(fov) => Projection::Perspective(PerspectiveProjection {
+                fov: fov.to_radians(),
+                near: 0.1,
+                far: self.distance(camera_config) + 1000.0,
+                ..default()
+            }),
+            None => Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::FixedVertical {
+                    viewport_height: self.view_height,
+                },
+                near: -1000.0,
+                far: 1000.0,
+                ..OrthographicProjection::default_3d()
+            }),
+        }
+    }
+}
+
+/// Tangent of half of `fov` degrees.
+fn half_tan(fov: f32) -> f32 {
+    (fov.to_radians() / 2.0).tan()
+}
+
+/// The active [`CameraMode`] and the blend into it after a switch.
+#[derive(Component, Debug, Clone)]
+pub struct CameraModeController {
+    pub mode: CameraMode,
+    /// View shown in the last frame.
+    pub current_view: Option<CameraView>,
+    /// View shown when the mode last changed, while still blending away from it.
+    pub transition_from: Option<CameraView>,
+    /// Seconds since the mode last changed.
+    pub transition_elapsed: f32,
+}
+
+impl CameraModeController {
+    pub fn new(camera_config: &CameraConfig) -> Self {
+        Self {
+            mode: camera_config.mode,
+            current_view: None,
+            transition_from: None,
+            transition_elapsed: 0.0,
+        }
+    }
+
+    /// Switches to `mode`, blending over from whatever the camera shows now.
+    pub fn set_mode(&mut self, mode: CameraMode) {
+        if mode == self.mode {
+            return;
+        }
+        self.mode = mode;
+        self.transition_from = self.current_view;
+        self.transition_elapsed = 0.0;
+    }
+}
+
+impl Default for CameraModeController {
+    fn default() -> Self {
+        Self::new(&CameraConfig::default())
+    }
+}
+
+/// Camera input sampled every frame and consumed by the next physics tick.
+#[derive(Component, Debug, Clone, Default)]
+pub struct CameraInput {
+    pub rotate_clockwise: bool,
+    pub rotate_counter_clockwise: bool,
+    /// Degrees of yaw (`x`) and pitch (`y`) dragged since a tick last consumed them.
+    pub orbit: Vec2,
+    /// Set when the mode switch is pressed; cleared once a tick has consumed it.
+    pub cycle_mode_requested: bool,
+}
+
 #[derive(Debug, Clone)]
 pub enum RotationState {
     Idle,
@@ -66,18 +344,59 @@ pub struct CameraRotationController {
     pub state: RotationState,
     pub throttle_cw: bool,  // clockwise throttle
     pub throttle_ccw: bool, // counter-clockwise throttle
+    /// Stops of the current level; empty when the camera rotates freely.
+    pub snap_angles: Vec<CameraStop>,
+    /// Pitch of stops that don't set their own.
+    pub default_pitch: f32,
 }
 
 impl CameraRotationController {
-    pub fn new(_camera_config: &CameraConfig) -> Self {
+    pub fn new(camera_config: &CameraConfig, scene_path: &str) -> Self {
         Self {
-            acceleration: 720.0,  // degrees/second^2
-            deceleration: 1440.0, // degrees/second^2
-            max_velocity: 540.0,  // degrees/second
-            momentum_threshold: 270.0,
+            acceleration: camera_config.rotation_acceleration,
+            deceleration: camera_config.rotation_deceleration,
+            max_velocity: camera_config.rotation_max_velocity,
+            momentum_threshold: camera_config.rotation_momentum_threshold,
             state: RotationState::Idle,
             throttle_cw: false,
             throttle_ccw: false,
+            snap_angles: camera_config.snap_angles_for(scene_path).to_vec(),
+            default_pitch: camera_config.default_pitch(),
+        }
+    }
+
+    /// Updates the tuning values while keeping any rotation in progress.
+    ///
+    /// Returns whether the snap angles or their pitch changed; a rotation in progress is then
+    /// dropped, as its target may no longer exist.
+    pub fn apply_config(&mut self, camera_config: &CameraConfig, scene_path: &str) -> bool {
+        self.acceleration = camera_config.rotation_acceleration;
+        self.deceleration = camera_config.rotation_deceleration;
+        self.max_velocity = camera_config.rotation_max_velocity;
+        self.momentum_threshold = camera_config.rotation_momentum_threshold;
+
+        let snap_angles = camera_config.snap_angles_for(scene_path);
+        let default_pitch = camera_config.default_pitch();
+        if snap_angles == self.snap_angles && default_pitch == self.default_pitch {
+            return false;
+        }
+        self.snap_angles = snap_angles.to_vec();
+        self.default_pitch = default_pitch;
+        self.state = RotationState::Idle;
+        true
+    }
+
+    /// Pitch of the stop at `index`.
+    pub fn snap_pitch(&self, index: usize) -> f32 {
+        self.snap_angles[index].pitch.unwrap_or(self.default_pitch)
+    }
+
+    /// Index of the stop after `index` when rotating in `direction`.
+    pub fn next_snap_index(&self, index: usize, direction: RotationDirection) -> usize {
+        let len = self.snap_angles.len();
+        match direction {
+            RotationDirection::Clockwise => (index + 1) % len,
+            RotationDirection::CounterClockwise => (index + len - 1) % len,
         }
     }
 
@@ -106,24 +425,24 @@ impl CameraRotationController {
 
 impl Default for CameraRotationController {
     fn default() -> Self {
-        Self {
-            acceleration: 720.0,
-            deceleration: 1440.0,
-            max_velocity: 540.0,
-            momentum_threshold: 270.0,
-            state: RotationState::Idle,
-            throttle_cw: false,
-            throttle_ccw: false,
-        }
+        Self::new(&CameraConfig::default(), DEFAULT_LEVEL_PATH)
     }
 }
 
+/// Orthographic viewport height: the player's chosen zoom, widened while the camera rotates.
 #[derive(Component, Debug)]
 pub struct CameraZoomController {
     pub base_zoom: f32,
     pub max_zoom_out: f32,
     pub current_zoom: f32,
     pub zoom_speed: f32,
+    /// Zoom the player asked for, within `min_zoom..=max_zoom`.
+    pub target_zoom: f32,
+    /// `target_zoom` smoothed; the zoom before any rotation zoom-out.
+    pub user_zoom: f32,
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+    pub smoothing: f32,
 }
 
 impl CameraZoomController {
@@ -133,12 +452,45 @@ impl CameraZoomController {
             max_zoom_out: camera_config.zoom_out_value,
             current_zoom: camera_config.base_zoom,
             zoom_speed: 20.0,
+            target_zoom: camera_config.base_zoom,
+            user_zoom: camera_config.base_zoom,
+            min_zoom: camera_config.min_zoom,
+            max_zoom: camera_config.max_zoom,
+            smoothing: camera_config.zoom_smoothing,
         }
     }
 
+    /// Updates the zoom limits; the current zoom eases towards them.
+    pub fn apply_config(&mut self, camera_config: &CameraConfig) {
+        self.base_zoom = camera_config.base_zoom;
+        self.max_zoom_out = camera_config.zoom_out_value;
+        self.min_zoom = camera_config.min_zoom;
+        self.max_zoom = camera_config.max_zoom;
+        self.smoothing = camera_config.zoom_smoothing;
+        self.target_zoom = self.target_zoom.clamp(self.min_zoom, self.max_zoom);
+    }
+
+    /// Scales the player's zoom by `factor`; above 1 zooms out.
+    pub fn zoom_by(&mut self, factor: f32) {
+        self.target_zoom = (self.target_zoom * factor).clamp(self.min_zoom, self.max_zoom);
+    }
+
+    /// Eases the player's zoom towards `target_zoom`, or snaps to it if `smoothing` is 0.
+    pub fn update_user_zoom(&mut self, time_delta: f32) {
+        let blend = if self.smoothing > 0.0 {
+            1.0 - (-self.smoothing * time_delta).exp()
+        } else {
+            1.0
+        };
+        self.user_zoom += (self.target_zoom - self.user_zoom) * blend;
+    }
+
+    /// Eases towards the player's zoom, widened in proportion to the rotation speed as
+    /// `max_zoom_out` is to `base_zoom`.
     pub fn update_zoom_for_velocity(&mut self, velocity: f32, max_velocity: f32, time_delta: f32) {
         let velocity_ratio = (velocity / max_velocity).min(1.0);
-        let target_zoom = self.base_zoom + (self.max_zoom_out - self.base_zoom) * velocity_ratio;
+        let zoom_out_ratio = self.max_zoom_out / self.base_zoom;
+        let target_zoom = self.user_zoom * (1.0 + (zoom_out_ratio - 1.0) * velocity_ratio);
 
         let zoom_diff = target_zoom - self.current_zoom;
         let zoom_change = zoom_diff * self.zoom_speed * time_delta;
@@ -148,16 +500,161 @@ impl CameraZoomController {
 
 impl Default for CameraZoomController {
     fn default() -> Self {
+        Self::new(&CameraConfig::default())
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct CameraPositionController {
+    /// Stop the camera last came to rest at, or passed on its way to the next one.
+    pub current_snap_index: usize,
+}
+
+impl CameraPositionController {
+    /// Starts at the snap angle [`CameraAngle::new`] starts at.
+    pub fn new(camera_config: &CameraConfig, scene_path: &str) -> Self {
+        let stops = camera_config.snap_angles_for(scene_path);
+        Self {
+            current_snap_index: nearest_stop(stops, camera_config.start_angle).unwrap_or(0),
+        }
+    }
+}
+
+impl Default for CameraPositionController {
+    fn default() -> Self {
+        Self::new(&CameraConfig::default(), DEFAULT_LEVEL_PATH)
+    }
+}
+
+/// World-space bounding box of the level's voxels, which the camera keeps its view inside.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct LevelBounds {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl LevelBounds {
+    pub fn clamp(&self, point: Vec3) -> Vec3 {
+        point.clamp(self.min, self.max)
+    }
+
+    /// These bounds with `margin` taken off each side, down to the centre on axes narrower than
+    /// twice the margin.
+    pub fn shrink(&self, margin: Vec3) -> Self {
+        let center = (self.min + self.max) / 2.0;
         Self {
-            base_zoom: BASE_ZOOM,
-            max_zoom_out: ZOOM_OUT_VALUE,
-            current_zoom: BASE_ZOOM,
-            zoom_speed: 20.0,
+            min: (self.min + margin).min(center),
+            max: (self.max - margin).max(center),
         }
     }
 }
 
+/// Point the camera looks at: the player, held back by a dead zone, led by its velocity and
+/// smoothed.
 #[derive(Component, Debug, Clone, Default)]
-pub struct CameraPositionController {
-    pub current_snap_index: usize,
+pub struct CameraFollow {
+    /// Smoothed point the camera is centred on; `None` until there is a player to snap to.
+    pub focus: Option<Vec3>,
+    /// Where the focus is heading.
+    pub target: Vec3,
+    pub velocity: Vec3,
+    /// Height of the player when it last stood on something, which the camera keeps to
+    /// through small jumps.
+    pub anchor_height: Option<f32>,
+}
+
+impl CameraFollow {
+    /// Centres the camera on `position` at once, as after a teleport.
+    pub fn snap(&mut self, position: Vec3) {
+        self.focus = Some(position);
+        self.target = position;
+        self.velocity = Vec3::ZERO;
+        self.anchor_height = Some(position.y);
+    }
+
+    /// Moves the focus after the player at `player`, with `forward` the camera's horizontal
+    /// viewing direction, and returns it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update(
+        &mut self,
+        player: Vec3,
+        player_velocity: Vec3,
+        grounded: bool,
+        forward: Vec3,
+        bounds: Option<&LevelBounds>,
+        camera_config: &CameraConfig,
+        time_delta: f32,
+    ) -> Vec3 {
+        let Some(focus) = self.focus else {
+            let focus = bounds.map_or(player, |bounds| bounds.clamp(player));
+            self.snap(focus);
+            self.anchor_height = Some(player.y);
+            return focus;
+        };
+
+        let anchor = match self.anchor_height {
+            Some(anchor) if !grounded => anchor,
+            _ => player.y,
+        };
+        self.anchor_height = Some(anchor);
+        // Rise only with jumps that would leave the view; drop with the player at once.
+        let height = if player.y < anchor {
+            player.y
+        } else {
+            anchor.max(player.y - camera_config.vertical_dead_zone)
+        };
+
+        let look_ahead = (player_velocity.with_y(0.0) * camera_config.look_ahead_time)
+            .clamp_length_max(camera_config.look_ahead_max);
+        let desired = player.with_y(height) + look_ahead;
+
+        let right = forward.cross(Vec3::Y);
+        let offset = desired - self.target;
+        let outside =
+            |distance: f32, size: f32| distance.signum() * (distance.abs() - size / 2.0).max(0.0);
+        let mut target = self.target
+            + right * outside(offset.dot(right), camera_config.dead_zone_width)
+            + forward * outside(offset.dot(forward), camera_config.dead_zone_depth);
+        target.y = desired.y;
+        if let Some(bounds) = bounds {
+            target = bounds.clamp(target);
+        }
+        self.target = target;
+
+        let mut focus = smooth_damp(
+            focus,
+            target,
+            &mut self.velocity,
+            camera_config.follow_smooth_time,
+            time_delta,
+        );
+        if let Some(bounds) = bounds {
+            focus = bounds.clamp(focus);
+        }
+        self.focus = Some(focus);
+        focus
+    }
+}
+
+/// Moves `current` towards `target` like a critically damped spring that takes about
+/// `smooth_time` seconds to arrive, carrying its `velocity` between calls.
+pub fn smooth_damp(
+    current: Vec3,
+    target: Vec3,
+    velocity: &mut Vec3,
+    smooth_time: f32,
+    time_delta: f32,
+) -> Vec3 {
+    if smooth_time <= 0.0 {
+        *velocity = Vec3::ZERO;
+        return target;
+    }
+    let omega = 2.0 / smooth_time;
+    let x = omega * time_delta;
+    // Padé approximation of exp(-x).
+    let decay = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+    let change = current - target;
+    let temp = (*velocity + omega * change) * time_delta;
+    *velocity = (*velocity - omega * temp) * decay;
+    target + (change + temp) * decay
 }
@@ -3,4 +3,4 @@ pub mod plugin;
 pub mod systems;
 
 pub use components::*;
-pub use plugin::plugin;
+pub use plugin::CameraPlugin;
@@ -1,16 +1,47 @@
 use super::systems::{
-    camera_follow_system, camera_input_system, camera_rotation_system, camera_zoom_system,
+    apply_camera_config_system, camera_follow_system, camera_input_system, camera_rotation_system,
+    camera_user_zoom_system, camera_zoom_system, record_camera_angle_system,
+    sample_camera_input_system,
 };
-use bevy::prelude::*;
+use crate::action::ActionState;
+use crate::config::Config;
+use crate::player::systems::player_input_system;
+use bevy::{app::RunFixedMainLoopSystem, prelude::*};
 
-pub fn plugin(app: &mut App) {
-    app.add_systems(
-        Update,
-        (
-            camera_input_system,
-            camera_follow_system.after(camera_input_system),
-            camera_zoom_system.after(camera_follow_system),
-            camera_rotation_system.after(camera_follow_system),
-        ),
-    );
+/// Camera rig that follows the player: snapped isometric by default, switchable to free-orbit
+/// and perspective third-person modes.
+///
+/// Rotation, orbiting and mode switches step on the fixed timestep so movement directions stay
+/// deterministic; the rendered view interpolates between ticks.
+#[derive(Default)]
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .init_resource::<ActionState>()
+            .add_systems(
+                RunFixedMainLoop,
+                sample_camera_input_system.in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop),
+            )
+            .add_systems(
+                FixedUpdate,
+                (camera_input_system, camera_rotation_system)
+                    .chain()
+                    .before(player_input_system),
+            )
+            .add_systems(FixedPostUpdate, record_camera_angle_system)
+            .add_systems(
+                Update,
+                (
+                    apply_camera_config_system.run_if(resource_changed::<Config>),
+                    (
+                        camera_user_zoom_system,
+                        camera_zoom_system,
+                        camera_follow_system,
+                    )
+                        .chain(),
+                ),
+            );
+    }
 }
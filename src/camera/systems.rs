@@ -1,51 +1,184 @@
 use super::components::{
-    CameraAngle, CameraPositionController, CameraRotationController, CameraZoomController,
-    RotationDirection, RotationState,
+    nearest_stop, CameraAngle, CameraAngleInterpolation, CameraFollow, CameraInput,
+    CameraModeController, CameraPositionController, CameraRotationController, CameraView,
+    CameraZoomController, LevelBounds, RotationDirection, RotationState,
 };
-use crate::config::{Config, CAMERA_ANGLES};
-use crate::player::Player;
-use bevy::{prelude::*, render::camera::ScalingMode};
+use crate::action::{Action, ActionState};
+use crate::config::Config;
+use crate::player::{GroundedState, Player};
+use crate::world::components::{Level, DEFAULT_LEVEL_PATH};
+use bevy::{input::mouse::AccumulatedMouseMotion, prelude::*, window::PrimaryWindow};
+use bevy_rapier3d::prelude::Velocity;
 
+pub fn sample_camera_input_system(
+    action_state: Res<ActionState>,
+    config: Res<Config>,
+    mouse_motion: Option<Res<AccumulatedMouseMotion>>,
+    mut camera_query: Query<&mut CameraInput>,
+) {
+    for mut camera_input in camera_query.iter_mut() {
+        camera_input.rotate_clockwise = action_state.pressed(Action::RotateCameraClockwise);
+        camera_input.rotate_counter_clockwise =
+            action_state.pressed(Action::RotateCameraCounterClockwise);
+        camera_input.cycle_mode_requested |= action_state.just_pressed(Action::CycleCameraMode);
+        if let Some(mouse_motion) = &mouse_motion {
+            if action_state.pressed(Action::OrbitCamera) {
+                camera_input.orbit += mouse_motion.delta * config.camera.orbit_sensitivity;
+            }
+        }
+    }
+}
+
+/// Consumes the sampled input: rotation throttle, mode switches and, in free modes, orbiting.
 pub fn camera_input_system(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
     config: Res<Config>,
-    mut camera_query: Query<&mut CameraRotationController>,
+    mut camera_query: Query<(
+        &mut CameraInput,
+        &mut CameraRotationController,
+        &mut CameraPositionController,
+        &mut CameraModeController,
+        &mut CameraAngle,
+    )>,
 ) {
-    if let Ok(mut rotation_controller) = camera_query.single_mut() {
-        let cw_pressed = keyboard_input.pressed(config.key_bindings.camera_rotate_clockwise);
-        let ccw_pressed =
-            keyboard_input.pressed(config.key_bindings.camera_rotate_counter_clockwise);
+    let Ok((
+        mut camera_input,
+        mut rotation_controller,
+        mut position_controller,
+        mut mode_controller,
+        mut camera_angle,
+    )) = camera_query.single_mut()
+    else {
+        return;
+    };
+    rotation_controller.set_throttle(
+        camera_input.rotate_clockwise,
+        camera_input.rotate_counter_clockwise,
+    );
+
+    if std::mem::take(&mut camera_input.cycle_mode_requested) {
+        let mode = mode_controller.mode.next();
+        mode_controller.set_mode(mode);
+        if !mode.is_free() {
+            rotation_controller.state = RotationState::Idle;
+            snap_to_nearest_stop(
+                &rotation_controller,
+                &mut position_controller,
+                &mut camera_angle,
+            );
+        }
+    }
 
-        rotation_controller.set_throttle(cw_pressed, ccw_pressed);
+    let orbit = std::mem::take(&mut camera_input.orbit);
+    if mode_controller.mode.is_free() && orbit != Vec2::ZERO {
+        let camera = &config.camera;
+        camera_angle.current_angle = (camera_angle.current_angle + orbit.x).rem_euclid(360.0);
+        camera_angle.pitch =
+            (camera_angle.pitch + orbit.y).clamp(camera.orbit_min_pitch, camera.orbit_max_pitch);
+    }
+}
+
+/// Moves the camera to the snap angle nearest to it, or only resets its pitch when there are
+/// none.
+fn snap_to_nearest_stop(
+    rotation_controller: &CameraRotationController,
+    position_controller: &mut CameraPositionController,
+    camera_angle: &mut CameraAngle,
+) {
+    match nearest_stop(&rotation_controller.snap_angles, camera_angle.current_angle) {
+        Some(index) => {
+            position_controller.current_snap_index = index;
+            camera_angle.current_angle = rotation_controller.snap_angles[index].yaw;
+            camera_angle.pitch = rotation_controller.snap_pitch(index);
+        }
+        None => camera_angle.pitch = rotation_controller.default_pitch,
+    }
+}
+
+type CameraConfigQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut CameraRotationController,
+        &'static mut CameraZoomController,
+        &'static mut CameraPositionController,
+        &'static mut CameraAngle,
+        Option<&'static mut CameraAngleInterpolation>,
+        Option<&'static CameraModeController>,
+    ),
+>;
+
+/// Applies a changed config to the camera, moving it to the nearest stop if the snap angles
+/// changed while snapping.
+pub fn apply_camera_config_system(
+    config: Res<Config>,
+    level: Option<Res<Level>>,
+    mut camera_query: CameraConfigQuery,
+) {
+    let scene_path = level
+        .as_ref()
+        .map_or(DEFAULT_LEVEL_PATH, |level| level.scene_path.as_str());
+    for (
+        mut rotation_controller,
+        mut zoom_controller,
+        mut position_controller,
+        mut camera_angle,
+        interpolation,
+        mode_controller,
+    ) in camera_query.iter_mut()
+    {
+        zoom_controller.apply_config(&config.camera);
+        let snap_angles_changed = rotation_controller.apply_config(&config.camera, scene_path);
+        let free = mode_controller.is_some_and(|controller| controller.mode.is_free());
+        if snap_angles_changed && !free {
+            snap_to_nearest_stop(
+                &rotation_controller,
+                &mut position_controller,
+                &mut camera_angle,
+            );
+            if let Some(mut interpolation) = interpolation {
+                interpolation.teleport(&camera_angle);
+            }
+        }
+    }
+}
+
+/// Applies the player's zoom: held zoom keys and triggers, the scroll wheel and pinch gestures.
+pub fn camera_user_zoom_system(
+    time: Res<Time>,
+    config: Res<Config>,
+    action_state: Res<ActionState>,
+    mut camera_query: Query<&mut CameraZoomController, With<Camera3d>>,
+) {
+    let held = action_state.value(Action::ZoomOut) - action_state.value(Action::ZoomIn);
+    let factor = config.camera.zoom_rate.powf(held * time.delta_secs()) * action_state.zoom().exp();
+
+    if let Ok(mut zoom_controller) = camera_query.single_mut() {
+        zoom_controller.zoom_by(factor);
+        zoom_controller.update_user_zoom(time.delta_secs());
     }
 }
 
 pub fn camera_zoom_system(
     time: Res<Time>,
-    mut camera_query: Query<
-        (
-            &CameraRotationController,
-            &mut CameraZoomController,
-            &mut Projection,
-        ),
-        With<Camera3d>,
-    >,
+    mut camera_query: Query<(&CameraRotationController, &mut CameraZoomController), With<Camera3d>>,
 ) {
-    if let Ok((rotation_controller, mut zoom_controller, mut projection)) =
-        camera_query.single_mut()
-    {
+    if let Ok((rotation_controller, mut zoom_controller)) = camera_query.single_mut() {
         let current_velocity = rotation_controller.get_current_velocity().abs();
         zoom_controller.update_zoom_for_velocity(
             current_velocity,
             rotation_controller.max_velocity,
             time.delta_secs(),
         );
+    }
+}
 
-        if let Projection::Orthographic(ref mut ortho) = projection.as_mut() {
-            ortho.scaling_mode = ScalingMode::FixedVertical {
-                viewport_height: zoom_controller.current_zoom,
-            };
-        }
+/// Keeps the camera angle of the last two fixed ticks for [`camera_follow_system`].
+pub fn record_camera_angle_system(
+    mut camera_query: Query<(&mut CameraAngleInterpolation, &CameraAngle)>,
+) {
+    for (mut interpolation, camera_angle) in camera_query.iter_mut() {
+        interpolation.previous =
+            std::mem::replace(&mut interpolation.current, camera_angle.clone());
     }
 }
 
@@ -56,26 +189,28 @@ pub fn camera_rotation_system(
             &mut CameraRotationController,
             &mut CameraPositionController,
             &mut CameraAngle,
+            Option<&CameraModeController>,
         ),
         With<Camera3d>,
     >,
 ) {
-    if let Ok((mut rotation_controller, mut position_controller, mut camera_angle)) =
-        camera_query.single_mut()
+    if let Ok((
+        mut rotation_controller,
+        mut position_controller,
+        mut camera_angle,
+        mode_controller,
+    )) = camera_query.single_mut()
     {
+        let free = rotation_controller.snap_angles.is_empty()
+            || mode_controller.is_some_and(|controller| controller.mode.is_free());
         match rotation_controller.state.clone() {
             RotationState::Idle => {
                 if let Some(direction) = rotation_controller.get_rotation_direction() {
-                    let current_index = position_controller.current_snap_index;
-                    let target_index = match direction {
-                        RotationDirection::Clockwise => (current_index + 1) % CAMERA_ANGLES.len(),
-                        RotationDirection::CounterClockwise => {
-                            if current_index == 0 {
-                                CAMERA_ANGLES.len() - 1
-                            } else {
-                                current_index - 1
-                            }
-                        }
+                    let target_index = if free {
+                        0
+                    } else {
+                        rotation_controller
+                            .next_snap_index(position_controller.current_snap_index, direction)
                     };
 
                     rotation_controller.state = RotationState::Rotating {
@@ -129,47 +264,39 @@ pub fn camera_rotation_system(
                     camera_angle.current_angle += 360.0;
                 }
 
-                let target_angle = CAMERA_ANGLES[new_target];
-                let angle_diff = match direction {
-                    RotationDirection::Clockwise => {
-                        let diff = target_angle - camera_angle.current_angle;
-                        if diff < 0.0 {
-                            diff + 360.0
-                        } else {
-                            diff
-                        }
-                    }
-                    RotationDirection::CounterClockwise => {
-                        let diff = camera_angle.current_angle - target_angle;
-                        if diff < 0.0 {
-                            diff + 360.0
-                        } else {
-                            diff
-                        }
-                    }
-                };
-
-                if angle_diff <= 5.0 && new_velocity <= rotation_controller.momentum_threshold {
-                    camera_angle.current_angle = target_angle;
-                    position_controller.current_snap_index = new_target;
-                    rotation_controller.state = RotationState::Idle;
-                } else if angle_diff <= 5.0 && new_velocity > rotation_controller.momentum_threshold
-                {
-                    let next_target = match direction {
-                        RotationDirection::Clockwise => (new_target + 1) % CAMERA_ANGLES.len(),
-                        RotationDirection::CounterClockwise => {
-                            if new_target == 0 {
-                                CAMERA_ANGLES.len() - 1
-                            } else {
-                                new_target - 1
-                            }
-                        }
+                // Rotating freely there is nothing to snap to; the camera coasts to a stop.
+                if !free {
+                    let angle_to = |from: f32, to: f32| match direction {
+                        RotationDirection::Clockwise => (to - from).rem_euclid(360.0),
+                        RotationDirection::CounterClockwise => (from - to).rem_euclid(360.0),
                     };
+                    let previous_index = position_controller.current_snap_index;
+                    let target_angle = rotation_controller.snap_angles[new_target].yaw;
+                    let angle_diff = angle_to(camera_angle.current_angle, target_angle);
 
-                    position_controller.current_snap_index = new_target;
-                    new_target = next_target;
+                    // Ease the pitch from the stop behind the camera to the one ahead of it.
+                    let span = angle_to(
+                        rotation_controller.snap_angles[previous_index].yaw,
+                        target_angle,
+                    );
+                    let span = if span == 0.0 { 360.0 } else { span };
+                    let progress = (1.0 - angle_diff / span).clamp(0.0, 1.0);
+                    camera_angle.pitch = rotation_controller
+                        .snap_pitch(previous_index)
+                        .lerp(rotation_controller.snap_pitch(new_target), progress);
 
-                    new_velocity *= 0.8;
+                    if angle_diff <= 5.0 && new_velocity <= rotation_controller.momentum_threshold {
+                        camera_angle.current_angle = target_angle;
+                        camera_angle.pitch = rotation_controller.snap_pitch(new_target);
+                        position_controller.current_snap_index = new_target;
+                        rotation_controller.state = RotationState::Idle;
+                        return;
+                    } else if angle_diff <= 5.0 {
+                        position_controller.current_snap_index = new_target;
+                        new_target = rotation_controller.next_snap_index(new_target, direction);
+
+                        new_velocity *= 0.8;
+                    }
                 }
 
                 if new_velocity <= 0.01 {
@@ -187,18 +314,90 @@ pub fn camera_rotation_system(
     }
 }
 
+/// Places the camera around the point it follows and sets its projection for the active mode,
+/// blending from the previous mode's view for `CameraConfig::mode_transition_time` after a
+/// switch.
+#[allow(clippy::type_complexity)]
 pub fn camera_follow_system(
-    mut camera_query: Query<(&CameraAngle, &mut Transform), With<Camera3d>>,
-    player_query: Query<&Transform, (With<Player>, Without<Camera3d>)>,
+    time: Res<Time>,
+    fixed_time: Res<Time<Fixed>>,
     config: Res<Config>,
+    bounds: Option<Res<LevelBounds>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut camera_query: Query<
+        (
+            &CameraAngleInterpolation,
+            &CameraZoomController,
+            &mut CameraModeController,
+            &mut CameraFollow,
+            &mut Transform,
+            &mut Projection,
+        ),
+        With<Camera3d>,
+    >,
+    player_query: Query<
+        (&Transform, Option<&Velocity>, Option<&GroundedState>),
+        (With<Player>, Without<Camera3d>),
+    >,
 ) {
-    if let Ok((camera_angle, mut transform)) = camera_query.single_mut() {
-        let player_pos = if let Ok(player_transform) = player_query.single() {
-            player_transform.translation
-        } else {
-            Vec3::ZERO
-        };
-
-        *transform = camera_angle.get_transform_from_angle(player_pos, &config.camera);
-    }
+    let Ok((
+        interpolation,
+        zoom_controller,
+        mut mode_controller,
+        mut follow,
+        mut transform,
+        mut projection,
+    )) = camera_query.single_mut()
+    else {
+        return;
+    };
+
+    // Rotation steps on the fixed timestep; show it smoothly between ticks.
+    let camera_angle = interpolation.interpolated(fixed_time.overstep_fraction());
+    let camera = &config.camera;
+    let target = CameraView::new(mode_controller.mode, &camera_angle, zoom_controller, camera);
+    let view = match mode_controller.transition_from {
+        Some(from) => {
+            mode_controller.transition_elapsed += time.delta_secs();
+            let t = if camera.mode_transition_time > 0.0 {
+                (mode_controller.transition_elapsed / camera.mode_transition_time).min(1.0)
+            } else {
+                1.0
+            };
+            if t >= 1.0 {
+                mode_controller.transition_from = None;
+                target
+            } else {
+                from.lerp(&target, t * t * (3.0 - 2.0 * t))
+            }
+        }
+        None => target,
+    };
+    mode_controller.current_view = Some(view);
+
+    // Keep the whole view inside the level, not just the point it is centred on.
+    let aspect_ratio = windows
+        .single()
+        .ok()
+        .filter(|window| window.height() > 0.0)
+        .map_or(1.0, |window| window.width() / window.height());
+    let bounds = bounds
+        .as_deref()
+        .filter(|_| camera.clamp_to_level)
+        .map(|bounds| bounds.shrink(view.ground_half_extents(aspect_ratio)));
+    let focus = match player_query.single() {
+        Ok((player_transform, velocity, grounded_state)) => follow.update(
+            player_transform.translation,
+            velocity.map_or(Vec3::ZERO, |velocity| velocity.linvel),
+            grounded_state.is_some_and(|state| state.is_grounded),
+            camera_angle.get_camera_forward_direction(),
+            bounds.as_ref(),
+            camera,
+            time.delta_secs(),
+        ),
+        Err(_) => follow.focus.unwrap_or(Vec3::ZERO),
+    };
+
+    *transform = view.transform(focus, camera);
+    *projection = view.projection(camera);
 }
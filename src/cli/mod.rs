@@ -0,0 +1,96 @@
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use clap::Parser;
+
+use voxel_sampo::config::{DEFAULT_CONFIG_PATH, DEFAULT_SETTINGS_PATH};
+use voxel_sampo::world::components::DEFAULT_LEVEL_PATH;
+
+pub const LEVEL_ASSET_SOURCE: &str = "level";
+
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    /// .vox scene to load: a file on disk, or a path inside `assets/`
+    #[arg(long, value_name = "PATH", default_value = DEFAULT_LEVEL_PATH)]
+    pub level: PathBuf,
+
+    /// RON config file; the defaults are used if it does not exist
+    #[arg(long, value_name = "PATH", default_value = DEFAULT_CONFIG_PATH)]
+    pub config: PathBuf,
+
+    /// RON file the in-game settings menu saves to; overrides the config's bindings
+    #[arg(long, value_name = "PATH", default_value = DEFAULT_SETTINGS_PATH)]
+    pub settings: PathBuf,
+
+    /// Window width in logical pixels
+    #[arg(long, default_value_t = 960, value_parser = clap::value_parser!(u32).range(1..))]
+    pub width: u32,
+
+    /// Window height in logical pixels
+    #[arg(long, default_value_t = 720, value_parser = clap::value_parser!(u32).range(1..))]
+    pub height: u32,
+
+    /// Start in borderless fullscreen on the current monitor
+    #[arg(long)]
+    pub fullscreen: bool,
+
+    /// Start with the debug overlay and physics debug rendering enabled
+    #[arg(long)]
+    pub debug: bool,
+
+    /// Player spawn position
+    #[arg(long, value_name = "X,Y,Z", default_value = "0,1,0", value_parser = parse_vec3)]
+    pub spawn: Vec3,
+
+    /// Record the input of every physics tick to this file, rewritten about once a second and on exit
+    #[arg(long, value_name = "PATH", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Play back a recording made with `--record` instead of reading the keyboard
+    #[arg(long, value_name = "PATH")]
+    pub replay: Option<PathBuf>,
+}
+
+impl Cli {
+    /// Directory to register as the `level://` asset source when `--level` is a file on disk.
+    pub fn level_source_dir(&self) -> Option<PathBuf> {
+        if !self.level.is_file() {
+            return None;
+        }
+        match self.level.parent() {
+            Some(parent) if parent != Path::new("") => Some(parent.to_path_buf()),
+            _ => Some(PathBuf::from(".")),
+        }
+    }
+
+    /// Asset path the level scene is loaded from.
+    pub fn level_asset_path(&self) -> String {
+        match (self.level_source_dir(), self.level.file_name()) {
+            (Some(_), Some(file_name)) => {
+                format!("{LEVEL_ASSET_SOURCE}://{}", file_name.to_string_lossy())
+            }
+            _ => self.level.to_string_lossy().into_owned(),
+        }
+    }
+}
+
+fn parse_vec3(value: &str) -> Result<Vec3, String> {
+    let components = value
+        .split(',')
+        .map(|component| {
+            component
+                .trim()
+                .parse::<f32>()
+                .map_err(|err| format!("`{component}`: {err}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match components.as_slice() {
+        [x, y, z] => Ok(Vec3::new(*x, *y, *z)),
+        _ => Err(format!(
+            "expected three comma-separated numbers, got {}",
+            components.len()
+        )),
+    }
+}
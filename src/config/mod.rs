@@ -1,46 +1,245 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, AxisDirection, Binding};
+use crate::camera::{CameraMode, CameraStop};
+use crate::platform::{PlatformMotion, PlatformSpec};
+use crate::voxel::SurfaceMaterial;
+
+mod error;
+pub mod plugin;
+pub mod settings;
+pub mod watcher;
+
+pub use error::ConfigError;
+pub use plugin::ConfigPlugin;
+pub use settings::{SettingsFile, UserSettings, DEFAULT_SETTINGS_PATH};
+pub use watcher::ConfigWatcher;
+
+pub const DEFAULT_CONFIG_PATH: &str = "config.ron";
 
 pub const PLAYER_MOVE_FORCE: f32 = 80.0;
 pub const PLAYER_MAX_SPEED: f32 = 4.0;
-pub const PLAYER_JUMP_FORCE: f32 = 50.0;
+pub const PLAYER_JUMP_HEIGHT: f32 = 1.3;
+pub const PLAYER_TIME_TO_APEX: f32 = 0.4;
+pub const PLAYER_FALL_GRAVITY_MULTIPLIER: f32 = 1.5;
+pub const PLAYER_JUMP_CUT: f32 = 0.5;
 pub const PLAYER_AIR_CONTROL_FORCE: f32 = 2.0;
+pub const PLAYER_GROUND_DAMPING: f32 = 8.0;
+pub const PLAYER_AIR_DAMPING: f32 = 0.1;
+pub const PLAYER_STEP_HEIGHT: f32 = 0.1;
+pub const PLAYER_MAX_SLOPE_ANGLE: f32 = 45.0;
+pub const PLAYER_COYOTE_TIME: f32 = 0.1;
+pub const PLAYER_JUMP_BUFFER_TIME: f32 = 0.1;
 pub const CAMERA_DISTANCE: f32 = 40.0;
 pub const CAMERA_HEIGHT: f32 = 24.0;
-pub const CAMERA_ANGLES: [f32; 4] = [60.0, 150.0, 240.0, 330.0];
+pub const CAMERA_SNAP_ANGLES: [f32; 4] = [60.0, 150.0, 240.0, 330.0];
+pub const CAMERA_START_ANGLE: f32 = 330.0;
+pub const CAMERA_MODE_TRANSITION_TIME: f32 = 0.5; // seconds
+pub const CAMERA_ORBIT_SENSITIVITY: f32 = 0.25; // degrees/pixel
+pub const CAMERA_ORBIT_MIN_PITCH: f32 = 5.0;
+pub const CAMERA_ORBIT_MAX_PITCH: f32 = 85.0;
+pub const CAMERA_THIRD_PERSON_DISTANCE: f32 = 6.0;
+pub const CAMERA_THIRD_PERSON_FOV: f32 = 60.0; // degrees
+pub const CAMERA_FOLLOW_SMOOTH_TIME: f32 = 0.15; // seconds
+pub const CAMERA_DEAD_ZONE_WIDTH: f32 = 1.0;
+pub const CAMERA_DEAD_ZONE_DEPTH: f32 = 0.6;
+pub const CAMERA_LOOK_AHEAD_TIME: f32 = 0.3; // seconds
+pub const CAMERA_LOOK_AHEAD_MAX: f32 = 1.5;
+pub const CAMERA_VERTICAL_DEAD_ZONE: f32 = 1.5;
+pub const CAMERA_ROTATION_ACCELERATION: f32 = 720.0; // degrees/second^2
+pub const CAMERA_ROTATION_DECELERATION: f32 = 1440.0; // degrees/second^2
+pub const CAMERA_ROTATION_MAX_VELOCITY: f32 = 540.0; // degrees/second
+pub const CAMERA_ROTATION_MOMENTUM_THRESHOLD: f32 = 270.0; // degrees/second
+pub const PHYSICS_TICK_RATE: f64 = 60.0; // ticks/second
+pub const GAMEPAD_STICK_DEAD_ZONE: f32 = 0.2;
 pub const BASE_ZOOM: f32 = 10.0;
 pub const ZOOM_OUT_VALUE: f32 = 20.0;
+pub const MIN_ZOOM: f32 = 4.0;
+pub const MAX_ZOOM: f32 = 30.0;
+pub const ZOOM_STEP: f32 = 1.15; // factor per scroll wheel notch
+pub const ZOOM_RATE: f32 = 2.0; // factor per second while a zoom key is held
+pub const ZOOM_SMOOTHING: f32 = 12.0; // 1/second
+pub const RESPAWN_KILL_HEIGHT: f32 = -20.0;
+pub const RESPAWN_INVULNERABILITY_TIME: f32 = 1.0;
+pub const RESPAWN_FADE_TIME: f32 = 0.5;
+pub const CUTAWAY_RADIUS: f32 = 1.5;
+pub const CUTAWAY_EDGE: f32 = 0.5;
+pub const CUTAWAY_OCCLUDER_OPACITY: f32 = 0.3;
+pub const CUTAWAY_ROOF_CLEARANCE: f32 = 2.5;
+
+/// Every binding of each [`Action`]; any of them triggers it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InputBindings {
+    pub move_forward: Vec<Binding>,
+    pub move_back: Vec<Binding>,
+    pub move_left: Vec<Binding>,
+    pub move_right: Vec<Binding>,
+    pub jump: Vec<Binding>,
+    pub dash: Vec<Binding>,
+    pub rotate_camera_clockwise: Vec<Binding>,
+    pub rotate_camera_counter_clockwise: Vec<Binding>,
+    pub orbit_camera: Vec<Binding>,
+    pub cycle_camera_mode: Vec<Binding>,
+    pub zoom_in: Vec<Binding>,
+    pub zoom_out: Vec<Binding>,
+    pub toggle_debug: Vec<Binding>,
+    pub toggle_settings: Vec<Binding>,
+    /// Stick deflection, from 0 to 1, below which gamepad axes are ignored.
+    pub stick_dead_zone: f32,
+}
+
+impl InputBindings {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        match action {
+            Action::MoveForward => &self.move_forward,
+            Action::MoveBack => &self.move_back,
+            Action::MoveLeft => &self.move_left,
+            Action::MoveRight => &self.move_right,
+            Action::Jump => &self.jump,
+            Action::Dash => &self.dash,
+            Action::RotateCameraClockwise => &self.rotate_camera_clockwise,
+            Action::RotateCameraCounterClockwise => &self.rotate_camera_counter_clockwise,
+            Action::OrbitCamera => &self.orbit_camera,
+            Action::CycleCameraMode => &self.cycle_camera_mode,
+            Action::ZoomIn => &self.zoom_in,
+            Action::ZoomOut => &self.zoom_out,
+            Action::ToggleDebug => &self.toggle_debug,
+            Action::ToggleSettings => &self.toggle_settings,
+        }
+    }
 
-#[derive(Debug, Clone)]
-pub struct KeyBindings {
-    pub camera_rotate_clockwise: KeyCode,
-    pub camera_rotate_counter_clockwise: KeyCode,
-    pub player_move_up: KeyCode,
-    pub player_move_down: KeyCode,
-    pub player_move_left: KeyCode,
-    pub player_move_right: KeyCode,
-    pub player_jump: KeyCode,
+    pub fn get_mut(&mut self, action: Action) -> &mut Vec<Binding> {
+        match action {
+            Action::MoveForward => &mut self.move_forward,
+            Action::MoveBack => &mut self.move_back,
+            Action::MoveLeft => &mut self.move_left,
+            Action::MoveRight => &mut self.move_right,
+            Action::Jump => &mut self.jump,
+            Action::Dash => &mut self.dash,
+            Action::RotateCameraClockwise => &mut self.rotate_camera_clockwise,
+            Action::RotateCameraCounterClockwise => &mut self.rotate_camera_counter_clockwise,
+            Action::OrbitCamera => &mut self.orbit_camera,
+            Action::CycleCameraMode => &mut self.cycle_camera_mode,
+            Action::ZoomIn => &mut self.zoom_in,
+            Action::ZoomOut => &mut self.zoom_out,
+            Action::ToggleDebug => &mut self.toggle_debug,
+            Action::ToggleSettings => &mut self.toggle_settings,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let stick_dead_zone = self.stick_dead_zone;
+        non_negative("bindings.stick_dead_zone", stick_dead_zone)?;
+        if stick_dead_zone >= 1.0 {
+            return Err(ConfigError::Invalid {
+                field: "bindings.stick_dead_zone",
+                reason: format!("must be less than 1, got {stick_dead_zone}"),
+            });
+        }
+        Ok(())
+    }
+
+    /// Other actions that `binding` also triggers.
+    pub fn conflicts(&self, action: Action, binding: Binding) -> Vec<Action> {
+        Action::ALL
+            .into_iter()
+            .filter(|&other| other != action && self.get(other).contains(&binding))
+            .collect()
+    }
+
+    /// Actions sharing at least one binding with another action.
+    pub fn conflicting_actions(&self) -> Vec<Action> {
+        Action::ALL
+            .into_iter()
+            .filter(|&action| {
+                self.get(action)
+                    .iter()
+                    .any(|&binding| !self.conflicts(action, binding).is_empty())
+            })
+            .collect()
+    }
+
+    /// Makes `binding` the only binding of its device kind for `action`.
+    ///
+    /// The binding is taken away from any other action that used it; those actions are returned.
+    pub fn rebind(&mut self, action: Action, binding: Binding) -> Vec<Action> {
+        let taken_from = self.conflicts(action, binding);
+        for &other in &taken_from {
+            self.get_mut(other).retain(|&existing| existing != binding);
+        }
+
+        let bindings = self.get_mut(action);
+        bindings.retain(|existing| existing.device() != binding.device());
+        bindings.push(binding);
+        taken_from
+    }
 }
 
-impl Default for KeyBindings {
+impl Default for InputBindings {
     fn default() -> Self {
+        use AxisDirection::{Negative, Positive};
+        use Binding::{GamepadAxis as Axis, GamepadButton as Button, Key};
+
         Self {
-            camera_rotate_clockwise: KeyCode::KeyE,
-            camera_rotate_counter_clockwise: KeyCode::KeyQ,
-            player_move_up: KeyCode::KeyW,
-            player_move_down: KeyCode::KeyS,
-            player_move_left: KeyCode::KeyA,
-            player_move_right: KeyCode::KeyD,
-            player_jump: KeyCode::Space,
+            move_forward: vec![Key(KeyCode::KeyW), Axis(GamepadAxis::LeftStickY, Positive)],
+            move_back: vec![Key(KeyCode::KeyS), Axis(GamepadAxis::LeftStickY, Negative)],
+            move_left: vec![Key(KeyCode::KeyA), Axis(GamepadAxis::LeftStickX, Negative)],
+            move_right: vec![Key(KeyCode::KeyD), Axis(GamepadAxis::LeftStickX, Positive)],
+            jump: vec![Key(KeyCode::Space), Button(GamepadButton::South)],
+            dash: vec![Key(KeyCode::ShiftLeft), Button(GamepadButton::West)],
+            rotate_camera_clockwise: vec![Key(KeyCode::KeyE), Button(GamepadButton::RightTrigger)],
+            rotate_camera_counter_clockwise: vec![
+                Key(KeyCode::KeyQ),
+                Button(GamepadButton::LeftTrigger),
+            ],
+            orbit_camera: vec![Binding::Mouse(MouseButton::Right)],
+            cycle_camera_mode: vec![Key(KeyCode::KeyC), Button(GamepadButton::North)],
+            zoom_in: vec![Key(KeyCode::Equal), Button(GamepadButton::RightTrigger2)],
+            zoom_out: vec![Key(KeyCode::Minus), Button(GamepadButton::LeftTrigger2)],
+            toggle_debug: vec![Key(KeyCode::F3), Button(GamepadButton::Select)],
+            toggle_settings: vec![Key(KeyCode::Escape), Button(GamepadButton::Start)],
+            stick_dead_zone: GAMEPAD_STICK_DEAD_ZONE,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct PlayerConfig {
+    /// Horizontal force applied while grounded.
     pub move_force: f32,
+    /// Horizontal speed cap in m/s; a partly deflected stick lowers it proportionally.
     pub max_speed: f32,
-    pub jump_force: f32,
+    /// Height in metres a held jump rises above the take-off point.
+    pub jump_height: f32,
+    /// Seconds a held jump takes to reach `jump_height`; sets the gravity while rising.
+    pub time_to_apex: f32,
+    /// Gravity while falling, relative to the gravity while rising.
+    pub fall_gravity_multiplier: f32,
+    /// Fraction of the upward speed kept when jump is released before the apex.
+    pub jump_cut: f32,
+    /// Horizontal force applied while airborne.
     pub air_control_force: f32,
+    /// Linear damping while grounded.
+    pub ground_damping: f32,
+    /// Linear damping while airborne.
+    pub air_damping: f32,
+    /// Restrict movement to the world axis closest to the input direction.
+    pub snap_to_axis: bool,
+    /// Tallest ledge in metres the player walks up without jumping.
+    pub step_height: f32,
+    /// Steepest surface in degrees the player can stand on; steeper ones are slid down.
+    pub max_slope_angle: f32,
+    /// Seconds after leaving the ground during which a jump is still allowed.
+    pub coyote_time: f32,
+    /// Seconds a jump pressed in the air is remembered and performed on landing.
+    pub jump_buffer_time: f32,
+    /// Abilities the player starts with.
+    pub abilities: AbilityConfig,
 }
 
 impl Default for PlayerConfig {
@@ -48,18 +247,244 @@ impl Default for PlayerConfig {
         Self {
             move_force: PLAYER_MOVE_FORCE,
             max_speed: PLAYER_MAX_SPEED,
-            jump_force: PLAYER_JUMP_FORCE,
+            jump_height: PLAYER_JUMP_HEIGHT,
+            time_to_apex: PLAYER_TIME_TO_APEX,
+            fall_gravity_multiplier: PLAYER_FALL_GRAVITY_MULTIPLIER,
+            jump_cut: PLAYER_JUMP_CUT,
             air_control_force: PLAYER_AIR_CONTROL_FORCE,
+            ground_damping: PLAYER_GROUND_DAMPING,
+            air_damping: PLAYER_AIR_DAMPING,
+            snap_to_axis: true,
+            step_height: PLAYER_STEP_HEIGHT,
+            max_slope_angle: PLAYER_MAX_SLOPE_ANGLE,
+            coyote_time: PLAYER_COYOTE_TIME,
+            jump_buffer_time: PLAYER_JUMP_BUFFER_TIME,
+            abilities: AbilityConfig::default(),
+        }
+    }
+}
+
+/// Abilities given to the player on spawn and whenever a reload changes them; `None` leaves an
+/// ability out.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AbilityConfig {
+    pub double_jump: Option<DoubleJumpConfig>,
+    pub wall_jump: Option<WallJumpConfig>,
+    pub dash: Option<DashConfig>,
+}
+
+/// Extra jumps in mid-air.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DoubleJumpConfig {
+    /// Jumps between landings.
+    pub charges: u32,
+    /// Seconds between uses.
+    pub cooldown: f32,
+}
+
+impl Default for DoubleJumpConfig {
+    fn default() -> Self {
+        Self {
+            charges: 1,
+            cooldown: 0.0,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+/// Jumps off a wall the player is pressed against while airborne.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WallJumpConfig {
+    /// Wall jumps between landings.
+    pub charges: u32,
+    /// Seconds between uses.
+    pub cooldown: f32,
+    /// Horizontal speed away from the wall in m/s.
+    pub push_speed: f32,
+}
+
+impl Default for WallJumpConfig {
+    fn default() -> Self {
+        Self {
+            charges: 3,
+            cooldown: 0.25,
+            push_speed: 4.0,
+        }
+    }
+}
+
+/// Short bursts of horizontal speed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DashConfig {
+    /// Dashes between landings.
+    pub charges: u32,
+    /// Seconds between uses.
+    pub cooldown: f32,
+    /// Speed in m/s during the dash.
+    pub speed: f32,
+    /// Seconds the dash lasts.
+    pub duration: f32,
+}
+
+impl Default for DashConfig {
+    fn default() -> Self {
+        Self {
+            charges: 1,
+            cooldown: 0.5,
+            speed: 12.0,
+            duration: 0.15,
+        }
+    }
+}
+
+impl AbilityConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if let Some(double_jump) = &self.double_jump {
+            at_least_one("player.abilities.double_jump.charges", double_jump.charges)?;
+            non_negative(
+                "player.abilities.double_jump.cooldown",
+                double_jump.cooldown,
+            )?;
+        }
+        if let Some(wall_jump) = &self.wall_jump {
+            at_least_one("player.abilities.wall_jump.charges", wall_jump.charges)?;
+            non_negative("player.abilities.wall_jump.cooldown", wall_jump.cooldown)?;
+            positive(
+                "player.abilities.wall_jump.push_speed",
+                wall_jump.push_speed,
+            )?;
+        }
+        if let Some(dash) = &self.dash {
+            at_least_one("player.abilities.dash.charges", dash.charges)?;
+            non_negative("player.abilities.dash.cooldown", dash.cooldown)?;
+            positive("player.abilities.dash.speed", dash.speed)?;
+            positive("player.abilities.dash.duration", dash.duration)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct CameraConfig {
+    /// Horizontal distance from the player.
     pub distance: f32,
+    /// Height above the player.
     pub height: f32,
+    /// Orthographic viewport height while idle, before the player zooms.
     pub base_zoom: f32,
+    /// Orthographic viewport height at full rotation speed, when the player hasn't zoomed.
+    /// Zooming scales it along with `base_zoom`.
     pub zoom_out_value: f32,
+    /// Range of viewport heights the player can zoom between.
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+    /// Zoom factor per scroll wheel notch.
+    pub zoom_step: f32,
+    /// Zoom factor per second while a zoom key or trigger is held.
+    pub zoom_rate: f32,
+    /// How quickly the zoom catches up with the player's input, in 1/second; 0 snaps to it.
+    pub zoom_smoothing: f32,
+    /// Rotation acceleration in degrees/second^2.
+    pub rotation_acceleration: f32,
+    /// Rotation deceleration in degrees/second^2.
+    pub rotation_deceleration: f32,
+    /// Rotation speed cap in degrees/second.
+    pub rotation_max_velocity: f32,
+    /// Speed in degrees/second above which the camera carries past a snap angle.
+    pub rotation_momentum_threshold: f32,
+    /// Stops the camera rotates between, in ascending order of angle around the player.
+    /// Empty lets the camera rotate freely.
+    pub snap_angles: Vec<CameraStop>,
+    /// Replacement `snap_angles` for particular levels, keyed by the level's file name, such as
+    /// `tower.vox`. Any directories in a key are ignored, so `levels/tower.vox` works too.
+    pub level_snap_angles: BTreeMap<String, Vec<CameraStop>>,
+    /// Angle in degrees the camera starts at, moved to the nearest snap angle if there are any.
+    pub start_angle: f32,
+    /// Mode the camera starts in.
+    pub mode: CameraMode,
+    /// Seconds a switch between camera modes takes.
+    pub mode_transition_time: f32,
+    /// Degrees the camera orbits per pixel of mouse drag.
+    pub orbit_sensitivity: f32,
+    /// Pitch range in degrees that orbiting is limited to.
+    pub orbit_min_pitch: f32,
+    pub orbit_max_pitch: f32,
+    /// Distance from the player in the perspective third-person mode.
+    pub third_person_distance: f32,
+    /// Vertical field of view in degrees of the third-person mode.
+    pub third_person_fov: f32,
+    /// Roughly the seconds the camera takes to catch up with the player, critically damped;
+    /// 0 follows rigidly.
+    pub follow_smooth_time: f32,
+    /// Size of the rectangle on the ground, across and along the view, that the player moves
+    /// in without the camera following.
+    pub dead_zone_width: f32,
+    pub dead_zone_depth: f32,
+    /// Seconds of the player's horizontal velocity the camera looks ahead by.
+    pub look_ahead_time: f32,
+    /// Longest look-ahead distance.
+    pub look_ahead_max: f32,
+    /// How far above the floor it last stood on the player can jump before the camera rises.
+    pub vertical_dead_zone: f32,
+    /// Keeps the camera's view inside the bounding box of the level's voxels.
+    pub clamp_to_level: bool,
+}
+
+/// Last component of a file path or asset path.
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+impl CameraConfig {
+    /// Snap angles used in the level loaded from `scene_path`, matched by file name so an asset
+    /// path such as `level://tower.vox` finds the entry for `levels/tower.vox`.
+    pub fn snap_angles_for(&self, scene_path: &str) -> &[CameraStop] {
+        let level = file_name(scene_path);
+        self.level_snap_angles
+            .iter()
+            .find(|(key, _)| file_name(key) == level)
+            .map_or(&self.snap_angles, |(_, stops)| stops)
+    }
+
+    /// Pitch in degrees of stops that don't set their own, given by `distance` and `height`.
+    pub fn default_pitch(&self) -> f32 {
+        self.height.atan2(self.distance).to_degrees()
+    }
+
+    fn validate_snap_angles(field: &'static str, stops: &[CameraStop]) -> Result<(), ConfigError> {
+        for stop in stops {
+            finite(field, stop.yaw)?;
+            if !(0.0..360.0).contains(&stop.yaw) {
+                return Err(ConfigError::Invalid {
+                    field,
+                    reason: format!("angles must be from 0 up to 360, got {}", stop.yaw),
+                });
+            }
+            if let Some(pitch) = stop.pitch {
+                finite(field, pitch)?;
+                if pitch.abs() >= 90.0 {
+                    return Err(ConfigError::Invalid {
+                        field,
+                        reason: format!("pitch must be between -90 and 90, got {pitch}"),
+                    });
+                }
+            }
+        }
+        if let Some(pair) = stops.windows(2).find(|pair| pair[0].yaw >= pair[1].yaw) {
+            return Err(ConfigError::Invalid {
+                field,
+                reason: format!(
+                    "angles must be in ascending order, got {} before {}",
+                    pair[0].yaw, pair[1].yaw
+                ),
+            });
+        }
+        Ok(())
+    }
 }
 
 impl Default for CameraConfig {
@@ -69,13 +494,575 @@ impl Default for CameraConfig {
             height: CAMERA_HEIGHT,
             base_zoom: BASE_ZOOM,
             zoom_out_value: ZOOM_OUT_VALUE,
+            min_zoom: MIN_ZOOM,
+            max_zoom: MAX_ZOOM,
+            zoom_step: ZOOM_STEP,
+            zoom_rate: ZOOM_RATE,
+            zoom_smoothing: ZOOM_SMOOTHING,
+            rotation_acceleration: CAMERA_ROTATION_ACCELERATION,
+            rotation_deceleration: CAMERA_ROTATION_DECELERATION,
+            rotation_max_velocity: CAMERA_ROTATION_MAX_VELOCITY,
+            rotation_momentum_threshold: CAMERA_ROTATION_MOMENTUM_THRESHOLD,
+            snap_angles: CAMERA_SNAP_ANGLES.map(CameraStop::new).to_vec(),
+            level_snap_angles: BTreeMap::new(),
+            start_angle: CAMERA_START_ANGLE,
+            mode: CameraMode::default(),
+            mode_transition_time: CAMERA_MODE_TRANSITION_TIME,
+            orbit_sensitivity: CAMERA_ORBIT_SENSITIVITY,
+            orbit_min_pitch: CAMERA_ORBIT_MIN_PITCH,
+            orbit_max_pitch: CAMERA_ORBIT_MAX_PITCH,
+            third_person_distance: CAMERA_THIRD_PERSON_DISTANCE,
+            third_person_fov: CAMERA_THIRD_PERSON_FOV,
+            follow_smooth_time: CAMERA_FOLLOW_SMOOTH_TIME,
+            dead_zone_width: CAMERA_DEAD_ZONE_WIDTH,
+            dead_zone_depth: CAMERA_DEAD_ZONE_DEPTH,
+            look_ahead_time: CAMERA_LOOK_AHEAD_TIME,
+            look_ahead_max: CAMERA_LOOK_AHEAD_MAX,
+            vertical_dead_zone: CAMERA_VERTICAL_DEAD_ZONE,
+            clamp_to_level: true,
         }
     }
 }
 
-#[derive(Resource, Debug, Clone, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PhysicsConfig {
+    /// Fixed simulation ticks per second, independent of the frame rate.
+    pub tick_rate: f64,
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            tick_rate: PHYSICS_TICK_RATE,
+        }
+    }
+}
+
+/// Surface of the voxels in each MagicaVoxel palette entry.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SurfaceConfig {
+    /// Surface of palette entries not matched below.
+    pub default: SurfaceMaterial,
+    /// Surfaces by palette index, from 1 to 255.
+    pub palette: BTreeMap<u8, SurfaceMaterial>,
+    /// Surfaces by the MagicaVoxel material type set on the palette entry, such as `glass`
+    /// or `emit`. Palette indices listed in `palette` take precedence.
+    pub material_types: BTreeMap<String, SurfaceMaterial>,
+}
+
+impl SurfaceConfig {
+    /// Surface of palette entry `index`, whose MagicaVoxel material type is `material_type`.
+    pub fn resolve(&self, index: u8, material_type: Option<&str>) -> SurfaceMaterial {
+        self.palette
+            .get(&index)
+            .or_else(|| material_type.and_then(|kind| self.material_types.get(kind)))
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.palette.contains_key(&0) {
+            return Err(ConfigError::Invalid {
+                field: "surfaces.palette",
+                reason: "palette index 0 is empty space and has no surface".to_string(),
+            });
+        }
+        let materials = std::iter::once(("surfaces.default", &self.default))
+            .chain(
+                self.palette
+                    .values()
+                    .map(|material| ("surfaces.palette", material)),
+            )
+            .chain(
+                self.material_types
+                    .values()
+                    .map(|material| ("surfaces.material_types", material)),
+            );
+        for (field, material) in materials {
+            non_negative(field, material.friction)?;
+            non_negative(field, material.restitution)?;
+        }
+        Ok(())
+    }
+}
+
+/// Palette entries whose voxels are trigger zones rather than geometry.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ZoneConfig {
+    /// Zone names by palette index, from 1 to 255.
+    pub palette: BTreeMap<u8, String>,
+}
+
+impl ZoneConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for (index, name) in &self.palette {
+            if *index == 0 {
+                return Err(ConfigError::Invalid {
+                    field: "zones.palette",
+                    reason: "palette index 0 is empty space and can't be a zone".to_string(),
+                });
+            }
+            if name.is_empty() {
+                return Err(ConfigError::Invalid {
+                    field: "zones.palette",
+                    reason: format!("palette index {index} has an empty zone name"),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where the player is caught after falling out of the world or touching something deadly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RespawnConfig {
+    /// Height below which the player is sent back to its last checkpoint.
+    pub kill_height: f32,
+    /// Zones that send the player back to its last checkpoint, matched as name prefixes.
+    pub kill_zones: Vec<String>,
+    /// Zones that become the player's checkpoint on entry, matched as name prefixes.
+    pub checkpoint_zones: Vec<String>,
+    /// Seconds after a respawn during which kill zones and lava are ignored.
+    pub invulnerability_time: f32,
+    /// Seconds the screen takes to fade back in from black after a respawn; 0 disables it.
+    pub fade_time: f32,
+}
+
+impl RespawnConfig {
+    pub fn is_kill_zone(&self, zone: &str) -> bool {
+        matches_prefix(&self.kill_zones, zone)
+    }
+
+    pub fn is_checkpoint_zone(&self, zone: &str) -> bool {
+        matches_prefix(&self.checkpoint_zones, zone)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        finite("respawn.kill_height", self.kill_height)?;
+        non_negative("respawn.invulnerability_time", self.invulnerability_time)?;
+        non_negative("respawn.fade_time", self.fade_time)?;
+        for (field, prefixes) in [
+            ("respawn.kill_zones", &self.kill_zones),
+            ("respawn.checkpoint_zones", &self.checkpoint_zones),
+        ] {
+            if prefixes.iter().any(String::is_empty) {
+                return Err(ConfigError::Invalid {
+                    field,
+                    reason: "an empty prefix would match every zone".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for RespawnConfig {
+    fn default() -> Self {
+        Self {
+            kill_height: RESPAWN_KILL_HEIGHT,
+            kill_zones: vec!["kill".to_string()],
+            checkpoint_zones: vec!["checkpoint".to_string()],
+            invulnerability_time: RESPAWN_INVULNERABILITY_TIME,
+            fade_time: RESPAWN_FADE_TIME,
+        }
+    }
+}
+
+/// How level geometry that hides the player is cut away.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CutawayConfig {
+    /// Fades out geometry between the camera and the player.
+    pub occlusion: bool,
+    /// Distance from the camera's line of sight to the player within which geometry fades.
+    pub radius: f32,
+    /// Width of the soft rim at the edge of `radius`.
+    pub edge: f32,
+    /// Share of an occluding surface's pixels still drawn: 0 hides it, 1 leaves it solid.
+    pub occluder_opacity: f32,
+    /// Height above the floor the player stands on from which everything is cut away, so roofs
+    /// and upper storeys don't hide the room; `None` keeps them.
+    pub roof_clearance: Option<f32>,
+}
+
+impl CutawayConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        positive("cutaway.radius", self.radius)?;
+        non_negative("cutaway.edge", self.edge)?;
+        if self.edge > self.radius {
+            return Err(ConfigError::Invalid {
+                field: "cutaway.edge",
+                reason: format!(
+                    "must be at most cutaway.radius ({}), got {}",
+                    self.radius, self.edge
+                ),
+            });
+        }
+        non_negative("cutaway.occluder_opacity", self.occluder_opacity)?;
+        if self.occluder_opacity > 1.0 {
+            return Err(ConfigError::Invalid {
+                field: "cutaway.occluder_opacity",
+                reason: format!("must be at most 1, got {}", self.occluder_opacity),
+            });
+        }
+        if let Some(roof_clearance) = self.roof_clearance {
+            positive("cutaway.roof_clearance", roof_clearance)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for CutawayConfig {
+    fn default() -> Self {
+        Self {
+            occlusion: true,
+            radius: CUTAWAY_RADIUS,
+            edge: CUTAWAY_EDGE,
+            occluder_opacity: CUTAWAY_OCCLUDER_OPACITY,
+            roof_clearance: Some(CUTAWAY_ROOF_CLEARANCE),
+        }
+    }
+}
+
+fn matches_prefix(prefixes: &[String], zone: &str) -> bool {
+    prefixes
+        .iter()
+        .any(|prefix| zone.starts_with(prefix.as_str()))
+}
+
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
     pub player: PlayerConfig,
     pub camera: CameraConfig,
-    pub key_bindings: KeyBindings,
+    pub physics: PhysicsConfig,
+    pub surfaces: SurfaceConfig,
+    pub zones: ZoneConfig,
+    pub respawn: RespawnConfig,
+    pub cutaway: CutawayConfig,
+    /// Moving platforms spawned with the level, on top of `Level::platforms`.
+    pub platforms: Vec<PlatformSpec>,
+    pub bindings: InputBindings,
+}
+
+impl Config {
+    /// Reads and validates a RON config file. Missing fields fall back to defaults.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let config: Self = ron::from_str(&contents).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Like [`Config::load`], but returns the defaults when the file does not exist.
+    pub fn load_or_default(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        match Self::load(path) {
+            Err(ConfigError::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => {
+                Ok(Self::default())
+            }
+            result => result,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let player = &self.player;
+        non_negative("player.move_force", player.move_force)?;
+        positive("player.max_speed", player.max_speed)?;
+        positive("player.jump_height", player.jump_height)?;
+        positive("player.time_to_apex", player.time_to_apex)?;
+        positive(
+            "player.fall_gravity_multiplier",
+            player.fall_gravity_multiplier,
+        )?;
+        non_negative("player.jump_cut", player.jump_cut)?;
+        if player.jump_cut > 1.0 {
+            return Err(ConfigError::Invalid {
+                field: "player.jump_cut",
+                reason: format!("must be at most 1, got {}", player.jump_cut),
+            });
+        }
+        non_negative("player.air_control_force", player.air_control_force)?;
+        non_negative("player.ground_damping", player.ground_damping)?;
+        non_negative("player.air_damping", player.air_damping)?;
+        non_negative("player.step_height", player.step_height)?;
+        non_negative("player.max_slope_angle", player.max_slope_angle)?;
+        if player.max_slope_angle >= 90.0 {
+            return Err(ConfigError::Invalid {
+                field: "player.max_slope_angle",
+                reason: format!("must be less than 90, got {}", player.max_slope_angle),
+            });
+        }
+        non_negative("player.coyote_time", player.coyote_time)?;
+        non_negative("player.jump_buffer_time", player.jump_buffer_time)?;
+        player.abilities.validate()?;
+
+        let camera = &self.camera;
+        positive("camera.distance", camera.distance)?;
+        finite("camera.height", camera.height)?;
+        positive("camera.base_zoom", camera.base_zoom)?;
+        positive("camera.zoom_out_value", camera.zoom_out_value)?;
+        if camera.zoom_out_value < camera.base_zoom {
+            return Err(ConfigError::Invalid {
+                field: "camera.zoom_out_value",
+                reason: format!(
+                    "must be at least camera.base_zoom ({}), got {}",
+                    camera.base_zoom, camera.zoom_out_value
+                ),
+            });
+        }
+        positive("camera.min_zoom", camera.min_zoom)?;
+        finite("camera.max_zoom", camera.max_zoom)?;
+        if !(camera.min_zoom..=camera.max_zoom).contains(&camera.base_zoom) {
+            return Err(ConfigError::Invalid {
+                field: "camera.base_zoom",
+                reason: format!(
+                    "must be between camera.min_zoom ({}) and camera.max_zoom ({}), got {}",
+                    camera.min_zoom, camera.max_zoom, camera.base_zoom
+                ),
+            });
+        }
+        for (field, factor) in [
+            ("camera.zoom_step", camera.zoom_step),
+            ("camera.zoom_rate", camera.zoom_rate),
+        ] {
+            finite(field, factor)?;
+            if factor < 1.0 {
+                return Err(ConfigError::Invalid {
+                    field,
+                    reason: format!("must be at least 1, got {factor}"),
+                });
+            }
+        }
+        non_negative("camera.zoom_smoothing", camera.zoom_smoothing)?;
+        positive("camera.rotation_acceleration", camera.rotation_acceleration)?;
+        positive("camera.rotation_deceleration", camera.rotation_deceleration)?;
+        positive("camera.rotation_max_velocity", camera.rotation_max_velocity)?;
+        non_negative(
+            "camera.rotation_momentum_threshold",
+            camera.rotation_momentum_threshold,
+        )?;
+        CameraConfig::validate_snap_angles("camera.snap_angles", &camera.snap_angles)?;
+        for stops in camera.level_snap_angles.values() {
+            CameraConfig::validate_snap_angles("camera.level_snap_angles", stops)?;
+        }
+        finite("camera.start_angle", camera.start_angle)?;
+        non_negative("camera.mode_transition_time", camera.mode_transition_time)?;
+        finite("camera.orbit_sensitivity", camera.orbit_sensitivity)?;
+        for (field, pitch) in [
+            ("camera.orbit_min_pitch", camera.orbit_min_pitch),
+            ("camera.orbit_max_pitch", camera.orbit_max_pitch),
+        ] {
+            finite(field, pitch)?;
+            if pitch.abs() >= 90.0 {
+                return Err(ConfigError::Invalid {
+                    field,
+                    reason: format!("must be between -90 and 90, got {pitch}"),
+                });
+            }
+        }
+        if camera.orbit_max_pitch < camera.orbit_min_pitch {
+            return Err(ConfigError::Invalid {
+                field: "camera.orbit_max_pitch",
+                reason: format!(
+                    "must be at least camera.orbit_min_pitch ({}), got {}",
+                    camera.orbit_min_pitch, camera.orbit_max_pitch
+                ),
+            });
+        }
+        positive("camera.third_person_distance", camera.third_person_distance)?;
+        positive("camera.third_person_fov", camera.third_person_fov)?;
+        if camera.third_person_fov >= 180.0 {
+            return Err(ConfigError::Invalid {
+                field: "camera.third_person_fov",
+                reason: format!("must be less than 180, got {}", camera.third_person_fov),
+            });
+        }
+        for (field, value) in [
+            ("camera.follow_smooth_time", camera.follow_smooth_time),
+            ("camera.dead_zone_width", camera.dead_zone_width),
+            ("camera.dead_zone_depth", camera.dead_zone_depth),
+            ("camera.look_ahead_time", camera.look_ahead_time),
+            ("camera.look_ahead_max", camera.look_ahead_max),
+            ("camera.vertical_dead_zone", camera.vertical_dead_zone),
+        ] {
+            non_negative(field, value)?;
+        }
+
+        let tick_rate = self.physics.tick_rate;
+        if !(tick_rate.is_finite() && tick_rate > 0.0) {
+            return Err(ConfigError::Invalid {
+                field: "physics.tick_rate",
+                reason: format!("must be greater than zero, got {tick_rate}"),
+            });
+        }
+
+        self.surfaces.validate()?;
+        self.zones.validate()?;
+        self.respawn.validate()?;
+        self.cutaway.validate()?;
+        for platform in &self.platforms {
+            validate_platform(platform)?;
+        }
+        self.bindings.validate()
+    }
+}
+
+fn validate_platform(platform: &PlatformSpec) -> Result<(), ConfigError> {
+    if platform.model.is_empty() {
+        return Err(ConfigError::Invalid {
+            field: "platforms.model",
+            reason: "must name a .vox file".to_string(),
+        });
+    }
+    let mut points = vec![platform.position];
+    match &platform.motion {
+        PlatformMotion::Waypoints {
+            points: waypoints,
+            speed,
+            pause,
+            ..
+        } => {
+            positive("platforms.motion.speed", *speed)?;
+            non_negative("platforms.motion.pause", *pause)?;
+            points.extend(waypoints);
+        }
+        PlatformMotion::Rotate { axis, speed } => {
+            finite("platforms.motion.speed", *speed)?;
+            if !axis.is_finite() || *axis == Vec3::ZERO {
+                return Err(ConfigError::Invalid {
+                    field: "platforms.motion.axis",
+                    reason: format!("must be a non-zero direction, got {axis}"),
+                });
+            }
+        }
+    }
+    match points.into_iter().find(|point| !point.is_finite()) {
+        Some(point) => Err(ConfigError::Invalid {
+            field: "platforms",
+            reason: format!("positions must be finite numbers, got {point}"),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn finite(field: &'static str, value: f32) -> Result<(), ConfigError> {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid {
+            field,
+            reason: format!("must be a finite number, got {value}"),
+        })
+    }
+}
+
+fn non_negative(field: &'static str, value: f32) -> Result<(), ConfigError> {
+    finite(field, value)?;
+    if value >= 0.0 {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid {
+            field,
+            reason: format!("must not be negative, got {value}"),
+        })
+    }
+}
+
+fn positive(field: &'static str, value: f32) -> Result<(), ConfigError> {
+    finite(field, value)?;
+    if value > 0.0 {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid {
+            field,
+            reason: format!("must be greater than zero, got {value}"),
+        })
+    }
+}
+
+fn at_least_one(field: &'static str, charges: u32) -> Result<(), ConfigError> {
+    if charges >= 1 {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid {
+            field,
+            reason: "must be at least 1; leave the ability out to disable it".to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Writes `contents` to a file of its own in the temp dir and loads it.
+    fn load_str(name: &str, contents: &str) -> Result<Config, ConfigError> {
+        let path =
+            std::env::temp_dir().join(format!("voxel-sampo-{name}-{}.ron", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        let result = Config::load(&path);
+        fs::remove_file(&path).unwrap();
+        result
+    }
+
+    fn invalid_field(result: Result<Config, ConfigError>) -> &'static str {
+        match result {
+            Err(ConfigError::Invalid { field, .. }) => field,
+            other => panic!("expected an invalid value, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn shipped_config_loads() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_CONFIG_PATH);
+        Config::load(path).unwrap();
+    }
+
+    #[test]
+    fn missing_fields_fall_back_to_defaults() {
+        let config = load_str("partial", "(player: (max_speed: 6.0))").unwrap();
+        assert_eq!(config.player.max_speed, 6.0);
+        assert_eq!(
+            config.player.jump_height,
+            PlayerConfig::default().jump_height
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let result = load_str("unknown-key", "(player: (max_sped: 6.0))");
+        assert!(matches!(result, Err(ConfigError::Parse { .. })));
+    }
+
+    #[test]
+    fn invalid_values_name_their_field() {
+        for (contents, field) in [
+            ("(player: (max_speed: 0.0))", "player.max_speed"),
+            ("(player: (jump_cut: 1.5))", "player.jump_cut"),
+            (
+                "(player: (max_slope_angle: 90.0))",
+                "player.max_slope_angle",
+            ),
+            (
+                "(camera: (base_zoom: 30.0, zoom_out_value: 20.0))",
+                "camera.zoom_out_value",
+            ),
+            ("(camera: (height: inf))", "camera.height"),
+        ] {
+            assert_eq!(invalid_field(load_str("invalid", contents)), field);
+        }
+    }
 }
@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+use super::settings::{
+    apply_user_settings_system, save_user_settings_system, ConfigBindings, SettingsFile,
+    UserSettings,
+};
+use super::watcher::{watch_config_file, ConfigWatcher};
+use super::Config;
+
+/// Provides the [`Config`] resource and optionally hot-reloads it from disk.
+#[derive(Default)]
+pub struct ConfigPlugin {
+    pub config: Config,
+    /// Config file to watch for changes.
+    pub watch_path: Option<PathBuf>,
+    /// Settings layered over the config, re-applied after every reload.
+    pub settings: UserSettings,
+    /// File the settings are saved to whenever they change.
+    pub settings_path: Option<PathBuf>,
+}
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        let mut config = self.config.clone();
+        self.settings.apply(&mut config);
+        app.insert_resource(ConfigBindings {
+            file: self.config.bindings.clone(),
+            applied: config.bindings.clone(),
+        })
+        .insert_resource(config)
+        .insert_resource(self.settings.clone());
+
+        if let Some(path) = &self.watch_path {
+            app.insert_resource(ConfigWatcher::new(path));
+        }
+        if let Some(path) = &self.settings_path {
+            app.insert_resource(SettingsFile { path: path.clone() });
+        }
+
+        app.add_systems(
+            Update,
+            (
+                watch_config_file.run_if(resource_exists::<ConfigWatcher>),
+                apply_user_settings_system
+                    .run_if(resource_changed::<UserSettings>.or(resource_changed::<Config>)),
+                save_user_settings_system.run_if(
+                    resource_exists::<SettingsFile>
+                        .and(resource_changed::<UserSettings>)
+                        .and(not(resource_added::<UserSettings>)),
+                ),
+            )
+                .chain(),
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use super::{Config, ConfigError, InputBindings};
+use crate::action::{Action, Binding};
+
+pub const DEFAULT_SETTINGS_PATH: &str = "settings.ron";
+
+/// Choices made in the in-game settings menu, layered over [`Config`].
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UserSettings {
+    /// Bindings of the actions rebound in the menu; every other action keeps its
+    /// `Config::bindings` entry.
+    pub bindings: Option<BTreeMap<Action, Vec<Binding>>>,
+}
+
+impl UserSettings {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        ron::from_str(&contents).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Like [`UserSettings::load`], but returns empty settings when the file does not exist.
+    pub fn load_or_default(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        match Self::load(path) {
+            Err(ConfigError::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => {
+                Ok(Self::default())
+            }
+            result => result,
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let path = path.as_ref();
+        let contents = ron::ser::to_string_pretty(self, PrettyConfig::new().depth_limit(2))?;
+        fs::write(path, contents).map_err(|source| ConfigError::Write {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Overrides the parts of `config` these settings cover.
+    pub fn apply(&self, config: &mut Config) {
+        self.apply_bindings(&mut config.bindings);
+    }
+
+    fn apply_bindings(&self, bindings: &mut InputBindings) {
+        for (&action, action_bindings) in self.bindings.iter().flatten() {
+            *bindings.get_mut(action) = action_bindings.clone();
+        }
+    }
+
+    /// Rebinds `action` in `bindings` and records every action that changed, returning the
+    /// actions the binding was taken from.
+    pub fn rebind(
+        &mut self,
+        bindings: &mut InputBindings,
+        action: Action,
+        binding: Binding,
+    ) -> Vec<Action> {
+        let taken_from = bindings.rebind(action, binding);
+        let overrides = self.bindings.get_or_insert_default();
+        for &changed in std::iter::once(&action).chain(&taken_from) {
+            overrides.insert(changed, bindings.get(changed).to_vec());
+        }
+        taken_from
+    }
+}
+
+/// Bindings from the config file, kept so clearing a setting restores what it overrode.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ConfigBindings {
+    pub file: InputBindings,
+    /// What [`apply_user_settings_system`] last left in `Config::bindings`.
+    pub applied: InputBindings,
+}
+
+/// File [`UserSettings`] are written to whenever they change.
+#[derive(Resource, Debug, Clone)]
+pub struct SettingsFile {
+    pub path: PathBuf,
+}
+
+pub fn apply_user_settings_system(
+    settings: Res<UserSettings>,
+    mut config: ResMut<Config>,
+    mut config_bindings: ResMut<ConfigBindings>,
+) {
+    // Any other bindings come from a config that was just (re)loaded.
+    if config.bindings != config_bindings.applied {
+        config_bindings.file = config.bindings.clone();
+    }
+
+    let mut bindings = config_bindings.file.clone();
+    settings.apply_bindings(&mut bindings);
+    config_bindings.applied = bindings.clone();
+
+    // Compare first so an unchanged config is not marked as changed.
+    if config.bindings != bindings {
+        config.bindings = bindings;
+    }
+}
+
+pub fn save_user_settings_system(settings: Res<UserSettings>, file: Res<SettingsFile>) {
+    match settings.save(&file.path) {
+        Ok(()) => info!("Saved settings to {}", file.path.display()),
+        Err(err) => error!("{err}"),
+    }
+}
@@ -0,0 +1,65 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use bevy::prelude::*;
+
+use super::Config;
+
+const CONFIG_POLL_INTERVAL_SECS: f32 = 0.5;
+
+/// Polls the config file and replaces the [`Config`] resource when it changes on disk.
+#[derive(Resource, Debug)]
+pub struct ConfigWatcher {
+    pub path: PathBuf,
+    last_modified: Option<SystemTime>,
+    timer: Timer,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self {
+            last_modified: modified_time(&path),
+            path,
+            timer: Timer::from_seconds(CONFIG_POLL_INTERVAL_SECS, TimerMode::Repeating),
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+pub fn watch_config_file(
+    time: Res<Time<Real>>,
+    mut watcher: ResMut<ConfigWatcher>,
+    mut config: ResMut<Config>,
+) {
+    if !watcher.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let modified = modified_time(&watcher.path);
+    if modified == watcher.last_modified {
+        return;
+    }
+    watcher.last_modified = modified;
+
+    // A deleted file keeps the current values until it comes back.
+    if modified.is_none() {
+        return;
+    }
+
+    match Config::load(&watcher.path) {
+        Ok(new_config) => {
+            info!("Reloaded config from {}", watcher.path.display());
+            *config = new_config;
+        }
+        Err(err) => error!("{err}; keeping the previous config"),
+    }
+}
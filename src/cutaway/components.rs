@@ -0,0 +1,41 @@
+use bevy::{
+    pbr::{ExtendedMaterial, MaterialExtension},
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderRef},
+};
+
+use super::settings::CutawaySettings;
+
+pub const CUTAWAY_SHADER_PATH: &str = "embedded://voxel_sampo/cutaway/cutaway.wgsl";
+
+/// Geometry this close above the floor is never faded, so the floor in front of the player
+/// stays solid.
+pub const FLOOR_MARGIN: f32 = 0.01;
+
+/// Level materials: a `StandardMaterial` whose fragments are cut away around the player.
+pub type CutawayMaterial = ExtendedMaterial<StandardMaterial, CutawayExtension>;
+
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone, Default)]
+pub struct CutawayExtension {
+    // Bindings 0-99 belong to the base material.
+    #[uniform(100)]
+    pub settings: CutawaySettings,
+}
+
+impl MaterialExtension for CutawayExtension {
+    fn fragment_shader() -> ShaderRef {
+        CUTAWAY_SHADER_PATH.into()
+    }
+
+    fn deferred_fragment_shader() -> ShaderRef {
+        CUTAWAY_SHADER_PATH.into()
+    }
+}
+
+/// The cutaway applied to every level material.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Cutaway {
+    /// Height of the floor the player last stood on, lowered while it falls below it.
+    pub floor_height: Option<f32>,
+    pub settings: CutawaySettings,
+}
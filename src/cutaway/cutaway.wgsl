@@ -0,0 +1,91 @@
+// `StandardMaterial` with level geometry cut away around the player: everything above the
+// roof height is discarded, and surfaces between the camera and the player are dithered.
+// `CutawaySettings::opacity_at` mirrors `cutaway_opacity`.
+
+#import bevy_pbr::{
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::alpha_discard,
+}
+
+#ifdef PREPASS_PIPELINE
+#import bevy_pbr::{
+    prepass_io::{VertexOutput, FragmentOutput},
+    pbr_deferred_functions::deferred_output,
+}
+#else
+#import bevy_pbr::{
+    forward_io::{VertexOutput, FragmentOutput},
+    pbr_functions::{apply_pbr_lighting, main_pass_post_lighting_processing},
+}
+#endif
+
+struct CutawaySettings {
+    player: vec3<f32>,
+    floor_height: f32,
+    to_camera: vec3<f32>,
+    radius: f32,
+    edge: f32,
+    occluder_opacity: f32,
+    roof_height: f32,
+}
+
+const FLOOR_MARGIN: f32 = 0.01;
+
+@group(2) @binding(100) var<uniform> cutaway: CutawaySettings;
+
+fn step_smooth(edge0: f32, edge1: f32, x: f32) -> f32 {
+    if edge0 >= edge1 {
+        return select(1.0, 0.0, x < edge0);
+    }
+    let t = clamp((x - edge0) / (edge1 - edge0), 0.0, 1.0);
+    return t * t * (3.0 - 2.0 * t);
+}
+
+fn cutaway_opacity(point: vec3<f32>) -> f32 {
+    if point.y > cutaway.roof_height {
+        return 0.0;
+    }
+    let offset = point - cutaway.player;
+    let along = dot(offset, cutaway.to_camera);
+    if along <= 0.0 || point.y <= cutaway.floor_height + FLOOR_MARGIN {
+        return 1.0;
+    }
+    let off_axis = length(offset - cutaway.to_camera * along);
+    let inside = 1.0 - step_smooth(cutaway.radius - cutaway.edge, cutaway.radius, off_axis);
+    return mix(1.0, cutaway.occluder_opacity, inside);
+}
+
+// Threshold from a 4x4 ordered dither pattern, strictly between 0 and 1.
+fn dither_threshold(frag_coord: vec2<f32>) -> f32 {
+    var bayer = array<f32, 16>(
+        0.0, 8.0, 2.0, 10.0,
+        12.0, 4.0, 14.0, 6.0,
+        3.0, 11.0, 1.0, 9.0,
+        15.0, 7.0, 13.0, 5.0,
+    );
+    let cell = vec2<u32>(frag_coord) % 4u;
+    return (bayer[cell.y * 4u + cell.x] + 0.5) / 16.0;
+}
+
+@fragment
+fn fragment(
+    in: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    let opacity = cutaway_opacity(in.world_position.xyz);
+    if opacity < dither_threshold(in.position.xy) {
+        discard;
+    }
+
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+#ifdef PREPASS_PIPELINE
+    let out = deferred_output(in, pbr_input);
+#else
+    var out: FragmentOutput;
+    out.color = apply_pbr_lighting(pbr_input);
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+#endif
+    return out;
+}
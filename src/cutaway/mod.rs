@@ -0,0 +1,8 @@
+pub mod components;
+pub mod plugin;
+pub mod settings;
+pub mod systems;
+
+pub use components::*;
+pub use plugin::CutawayPlugin;
+pub use settings::CutawaySettings;
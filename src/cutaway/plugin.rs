@@ -0,0 +1,34 @@
+use bevy::{asset::embedded_asset, prelude::*};
+
+use super::components::{Cutaway, CutawayMaterial};
+use super::systems::{
+    apply_cutaway_material_system, sync_cutaway_materials_system, update_cutaway_system,
+};
+use crate::camera::systems::camera_follow_system;
+use crate::config::Config;
+
+/// Cuts away level geometry that would hide the player: surfaces between the camera and the
+/// player are dithered out and everything above the player's storey is removed.
+///
+/// Cut geometry still casts shadows, so interiors stay lit as they would be with the roof on.
+#[derive(Default)]
+pub struct CutawayPlugin;
+
+impl Plugin for CutawayPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "cutaway.wgsl");
+        app.init_resource::<Config>()
+            .init_resource::<Cutaway>()
+            .add_plugins(MaterialPlugin::<CutawayMaterial>::default())
+            .add_systems(
+                Update,
+                (
+                    update_cutaway_system,
+                    apply_cutaway_material_system,
+                    sync_cutaway_materials_system.run_if(resource_changed::<Cutaway>),
+                )
+                    .chain()
+                    .after(camera_follow_system),
+            );
+    }
+}
@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+
+use super::components::FLOOR_MARGIN;
+use crate::config::CutawayConfig;
+
+pub use uniform::CutawaySettings;
+
+// The layout checks `ShaderType` generates for each field are never called.
+#[expect(dead_code)]
+mod uniform {
+    use bevy::{prelude::*, render::render_resource::ShaderType};
+
+    /// What `cutaway.wgsl` cuts away this frame, in world space.
+    #[derive(ShaderType, Reflect, Debug, Clone, Copy, PartialEq)]
+    pub struct CutawaySettings {
+        pub player: Vec3,
+        /// Height of the floor the player stands on; nothing at or below it fades.
+        pub floor_height: f32,
+        /// Unit direction from the player towards the camera along its line of sight.
+        pub to_camera: Vec3,
+        pub radius: f32,
+        pub edge: f32,
+        /// 1 when occlusion fading is off.
+        pub occluder_opacity: f32,
+        /// Height above which everything is cut away; `f32::MAX` when roofs are kept.
+        pub roof_height: f32,
+    }
+}
+
+impl CutawaySettings {
+    pub fn new(config: &CutawayConfig, player: Vec3, floor_height: f32, to_camera: Vec3) -> Self {
+        Self {
+            player,
+            floor_height,
+            to_camera,
+            radius: config.radius,
+            edge: config.edge,
+            occluder_opacity: if config.occlusion {
+                config.occluder_opacity
+            } else {
+                1.0
+            },
+            roof_height: config
+                .roof_clearance
+                .map_or(f32::MAX, |clearance| floor_height + clearance),
+        }
+    }
+
+    /// Share of the surface at `point` that is drawn, as `cutaway.wgsl` computes it.
+    pub fn opacity_at(&self, point: Vec3) -> f32 {
+        if point.y > self.roof_height {
+            return 0.0;
+        }
+        let offset = point - self.player;
+        let along = offset.dot(self.to_camera);
+        if along <= 0.0 || point.y <= self.floor_height + FLOOR_MARGIN {
+            return 1.0;
+        }
+        let off_axis = (offset - self.to_camera * along).length();
+        let inside = 1.0 - smoothstep(self.radius - self.edge, self.radius, off_axis);
+        1.0.lerp(self.occluder_opacity, inside)
+    }
+}
+
+impl Default for CutawaySettings {
+    fn default() -> Self {
+        Self::new(
+            &CutawayConfig {
+                occlusion: false,
+                roof_clearance: None,
+                ..default()
+            },
+            Vec3::ZERO,
+            0.0,
+            Vec3::Y,
+        )
+    }
+}
+
+/// `smoothstep` as `cutaway.wgsl` has it: a hard step when there is no edge to smooth over.
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    if edge0 >= edge1 {
+        return if x < edge0 { 0.0 } else { 1.0 };
+    }
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
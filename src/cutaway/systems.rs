@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use super::components::{Cutaway, CutawayExtension, CutawayMaterial};
+use super::settings::CutawaySettings;
+use crate::config::Config;
+use crate::entities::player::PLAYER_HALF_SIZE;
+use crate::player::{GroundedState, Player};
+use crate::world::components::Room;
+
+/// Aims the cutaway along the camera's line of sight to the player and tracks the floor the
+/// player stands on, which stays put through jumps.
+#[allow(clippy::type_complexity)]
+pub fn update_cutaway_system(
+    config: Res<Config>,
+    mut cutaway: ResMut<Cutaway>,
+    player_query: Query<(&Transform, &GroundedState), With<Player>>,
+    camera_query: Query<(&Transform, &Projection), (With<Camera3d>, Without<Player>)>,
+) {
+    let (Ok((player_transform, grounded_state)), Ok((camera_transform, projection))) =
+        (player_query.single(), camera_query.single())
+    else {
+        return;
+    };
+    let player = player_transform.translation;
+    let feet = player.y - PLAYER_HALF_SIZE;
+    let floor_height = match (grounded_state.is_grounded, grounded_state.ground_distance) {
+        (true, Some(distance)) => feet - distance,
+        // Airborne, the floor only follows the player down, to a lower storey.
+        _ => cutaway.floor_height.map_or(feet, |floor| floor.min(feet)),
+    };
+
+    // Orthographic sight lines are parallel, whether or not the camera is centred on the player.
+    let back = camera_transform.back();
+    let to_camera = match projection {
+        Projection::Orthographic(_) => *back,
+        _ => (camera_transform.translation - player).normalize_or(*back),
+    };
+
+    let settings = CutawaySettings::new(&config.cutaway, player, floor_height, to_camera);
+    if cutaway.floor_height != Some(floor_height) || cutaway.settings != settings {
+        cutaway.floor_height = Some(floor_height);
+        cutaway.settings = settings;
+    }
+}
+
+/// Gives the meshes of the level scene a [`CutawayMaterial`] in place of their
+/// `StandardMaterial`, sharing one per original material.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_cutaway_material_system(
+    mut commands: Commands,
+    mut replacements: Local<HashMap<AssetId<StandardMaterial>, Handle<CutawayMaterial>>>,
+    cutaway: Res<Cutaway>,
+    standard_materials: Res<Assets<StandardMaterial>>,
+    mut cutaway_materials: ResMut<Assets<CutawayMaterial>>,
+    query: Query<(Entity, &MeshMaterial3d<StandardMaterial>), With<Mesh3d>>,
+    parents: Query<&ChildOf>,
+    rooms: Query<(), With<Room>>,
+) {
+    for (entity, material) in query.iter() {
+        if !parents
+            .iter_ancestors(entity)
+            .any(|ancestor| rooms.contains(ancestor))
+        {
+            continue;
+        }
+        let replacement = match replacements.get(&material.id()) {
+            Some(replacement) => replacement.clone(),
+            None => {
+                // Scene materials load with the scene; try again once this one has.
+                let Some(base) = standard_materials.get(&material.0) else {
+                    continue;
+                };
+                let replacement = cutaway_materials.add(CutawayMaterial {
+                    base: base.clone(),
+                    extension: CutawayExtension {
+                        settings: cutaway.settings,
+                    },
+                });
+                replacements.insert(material.id(), replacement.clone());
+                replacement
+            }
+        };
+        commands
+            .entity(entity)
+            .remove::<MeshMaterial3d<StandardMaterial>>()
+            .insert(MeshMaterial3d(replacement));
+    }
+}
+
+pub fn sync_cutaway_materials_system(
+    cutaway: Res<Cutaway>,
+    mut cutaway_materials: ResMut<Assets<CutawayMaterial>>,
+) {
+    for (_, material) in cutaway_materials.iter_mut() {
+        material.extension.settings = cutaway.settings;
+    }
+}
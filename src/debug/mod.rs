@@ -2,4 +2,4 @@ pub mod components;
 pub mod plugin;
 pub mod systems;
 
-pub use plugin::plugin;
+pub use plugin::DebugPlugin;
@@ -1,17 +1,31 @@
 use super::components::*;
 use super::systems::*;
+use crate::action::ActionState;
 use bevy::prelude::*;
 
-pub fn plugin(app: &mut App) {
-    app.init_resource::<DebugState>()
-        .add_systems(Startup, setup_debug_ui)
-        .add_systems(
-            Update,
-            (
-                toggle_debug_mode,
-                toggle_debug_visibility,
-                update_debug_text,
-                visualize_ground_rays_system,
-            ),
-        );
+/// F3 debug overlay.
+#[derive(Default)]
+pub struct DebugPlugin {
+    /// Whether the overlay starts visible.
+    pub enabled: bool,
+}
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionState>()
+            .insert_resource(DebugState {
+                enabled: self.enabled,
+                show_physics_debug: self.enabled,
+            })
+            .add_systems(Startup, setup_debug_ui)
+            .add_systems(
+                Update,
+                (
+                    toggle_debug_mode,
+                    toggle_debug_visibility,
+                    update_debug_text,
+                    visualize_ground_rays_system,
+                ),
+            );
+    }
 }
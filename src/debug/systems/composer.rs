@@ -1,31 +1,22 @@
 use super::super::components::*;
 use super::{
-    fps::FpsInfo, performance::PerformanceInfo, physics::PhysicsInfo, player::PlayerInfo,
+    fps::FpsInfo,
+    performance::PerformanceInfo,
+    physics::{PhysicsInfo, PlayerPhysicsQuery},
+    player::PlayerInfo,
     world::WorldInfo,
 };
 use crate::camera::CameraAngle;
-use crate::physics::DynamicDamping;
-use crate::player::{GroundedState, Player};
+use crate::player::Player;
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
-// Type complexity warning suppressed for debug systems
-
+#[allow(clippy::too_many_arguments)]
 pub fn update_debug_text(
     debug_state: Res<DebugState>,
     mut debug_text_query: Query<&mut Text, With<DebugText>>,
     player_query: Query<&Transform, With<Player>>,
-    physics_query: Query<
-        (
-            &GroundedState,
-            &DynamicDamping,
-            &Damping,
-            &GravityScale,
-            &Friction,
-            &Restitution,
-        ),
-        With<Player>,
-    >,
+    physics_query: PlayerPhysicsQuery,
     camera_query: Query<&CameraAngle>,
     entity_query: Query<Entity>,
     rigidbody_query: Query<&Velocity, With<RigidBody>>,
@@ -53,17 +44,7 @@ fn compose_debug_info(
     entity_query: &Query<Entity>,
     rigidbody_query: &Query<&Velocity, With<RigidBody>>,
     player_query: &Query<&Transform, With<Player>>,
-    physics_query: &Query<
-        (
-            &GroundedState,
-            &DynamicDamping,
-            &Damping,
-            &GravityScale,
-            &Friction,
-            &Restitution,
-        ),
-        With<Player>,
-    >,
+    physics_query: &PlayerPhysicsQuery,
     camera_query: &Query<&CameraAngle>,
 ) -> String {
     let mut debug_info = String::new();
@@ -1,16 +1,19 @@
 use super::super::components::*;
+use crate::action::{Action, ActionState};
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
 pub fn toggle_debug_mode(
     mut debug_state: ResMut<DebugState>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut debug_render_context: ResMut<DebugRenderContext>,
+    action_state: Res<ActionState>,
+    debug_render_context: Option<ResMut<DebugRenderContext>>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::F3) {
+    if action_state.just_pressed(Action::ToggleDebug) {
         debug_state.enabled = !debug_state.enabled;
         debug_state.show_physics_debug = debug_state.enabled;
-        debug_render_context.enabled = debug_state.show_physics_debug;
+        if let Some(mut debug_render_context) = debug_render_context {
+            debug_render_context.enabled = debug_state.show_physics_debug;
+        }
     }
 }
 
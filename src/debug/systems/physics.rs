@@ -4,6 +4,19 @@ use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
 // Type alias to reduce complexity warnings
+pub type PlayerPhysicsQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static GroundedState,
+        &'static DynamicDamping,
+        &'static Damping,
+        &'static GravityScale,
+        &'static Friction,
+        &'static Restitution,
+    ),
+    With<Player>,
+>;
 
 pub struct PhysicsInfo {
     pub is_grounded: bool,
@@ -17,19 +30,7 @@ pub struct PhysicsInfo {
 }
 
 impl PhysicsInfo {
-    pub fn from_queries(
-        player_query: &Query<
-            (
-                &GroundedState,
-                &DynamicDamping,
-                &Damping,
-                &GravityScale,
-                &Friction,
-                &Restitution,
-            ),
-            With<Player>,
-        >,
-    ) -> Self {
+    pub fn from_queries(player_query: &PlayerPhysicsQuery) -> Self {
         if let Ok((
             grounded_state,
             dynamic_damping,
@@ -71,6 +72,32 @@ impl PhysicsInfo {
         let mut info = format!("Grounded: {}", if self.is_grounded { "Yes" } else { "No" });
 
         if let Some(grounded_state) = &self.grounded_state {
+            if self.is_grounded {
+                info.push_str(&format!(" ({:.1}s)", grounded_state.grounded_time));
+            } else {
+                info.push_str(&format!(" (airborne {:.1}s)", grounded_state.airborne_time));
+            }
+            let normal = grounded_state.ground_normal;
+            info.push_str(&format!(
+                "\nGround Normal: ({:.2}, {:.2}, {:.2})",
+                normal.x, normal.y, normal.z
+            ));
+            match grounded_state.ground_distance {
+                Some(distance) => info.push_str(&format!("\nGround Distance: {distance:.2}")),
+                None => info.push_str("\nGround Distance: -"),
+            }
+            match grounded_state.ground_entity {
+                Some(entity) => info.push_str(&format!("\nGround Entity: {entity}")),
+                None => info.push_str("\nGround Entity: -"),
+            }
+            match grounded_state.ground_material {
+                Some(material) => info.push_str(&format!("\nGround Material: {material}")),
+                None => info.push_str("\nGround Material: -"),
+            }
+            match grounded_state.ground_surface {
+                Some(surface) => info.push_str(&format!("\nGround Surface: {surface:?}")),
+                None => info.push_str("\nGround Surface: -"),
+            }
             info.push_str(&format!(
                 "\nGround Rays (9-point grid):\n  Hits: {}/{}\n  Sample Ray Origin: {:?}\n  Sample Ray Direction: {:?}\n  Ray Distance: {:.2}",
                 grounded_state.hit_count, grounded_state.rays.len(),
@@ -1,14 +1,22 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
-use crate::physics::DynamicDamping;
-use crate::player::{GroundedState, Player};
+use crate::physics::{DynamicDamping, TranslationInterpolation};
+use crate::platform::PlatformCarry;
+use crate::player::{GroundedState, JumpTimers, Player, PlayerInput};
+use crate::respawn::{Checkpoint, Invulnerable};
 
 #[derive(Bundle)]
 pub struct PlayerBundle {
     pub player: Player,
+    pub player_input: PlayerInput,
     pub grounded_state: GroundedState,
+    pub jump_timers: JumpTimers,
+    pub platform_carry: PlatformCarry,
+    pub checkpoint: Checkpoint,
+    pub invulnerable: Invulnerable,
     pub dynamic_damping: DynamicDamping,
+    pub translation_interpolation: TranslationInterpolation,
     pub mesh: Mesh3d,
     pub material: MeshMaterial3d<StandardMaterial>,
     pub transform: Transform,
@@ -16,6 +24,7 @@ pub struct PlayerBundle {
     pub collider: Collider,
     pub external_force: ExternalForce,
     pub velocity: Velocity,
+    pub mass_properties: ReadMassProperties,
     pub restitution: Restitution,
     pub friction: Friction,
     pub damping: Damping,
@@ -3,20 +3,27 @@ use bevy::{
         bloom::Bloom, core_3d::ScreenSpaceTransmissionQuality, tonemapping::Tonemapping,
     },
     prelude::*,
-    render::camera::ScalingMode,
 };
 
 use crate::camera::{
-    CameraAngle, CameraPositionController, CameraRotationController, CameraZoomController,
+    CameraAngle, CameraAngleInterpolation, CameraFollow, CameraInput, CameraModeController,
+    CameraPositionController, CameraRotationController, CameraView, CameraZoomController,
 };
 use crate::config::Config;
+use crate::world::components::Level;
 
-pub fn spawn_camera(commands: &mut Commands, config: &Res<Config>) {
-    let rotation_controller = CameraRotationController::new(&config.camera);
+pub fn spawn_camera(commands: &mut Commands, config: &Res<Config>, level: &Level) {
+    let scene_path = level.scene_path.as_str();
+    let rotation_controller = CameraRotationController::new(&config.camera, scene_path);
     let zoom_controller = CameraZoomController::new(&config.camera);
-    let camera_angle = CameraAngle::default();
-
-    let initial_transform = camera_angle.get_transform_from_angle(Vec3::ZERO, &config.camera);
+    let camera_angle = CameraAngle::new(&config.camera, scene_path);
+    let mode_controller = CameraModeController::new(&config.camera);
+    let view = CameraView::new(
+        mode_controller.mode,
+        &camera_angle,
+        &zoom_controller,
+        &config.camera,
+    );
 
     commands.spawn((
         Camera3d {
@@ -28,23 +35,20 @@ pub fn spawn_camera(commands: &mut Commands, config: &Res<Config>) {
             hdr: true,
             ..Default::default()
         },
-        initial_transform,
-        Projection::Orthographic(OrthographicProjection {
-            scaling_mode: ScalingMode::FixedVertical {
-                viewport_height: config.camera.base_zoom,
-            },
-            near: -1000.0,
-            far: 1000.0,
-            ..OrthographicProjection::default_3d()
-        }),
+        view.transform(Vec3::ZERO, &config.camera),
+        view.projection(&config.camera),
         Tonemapping::SomewhatBoringDisplayTransform,
         Bloom {
             intensity: 0.3,
             ..default()
         },
+        CameraAngleInterpolation::new(&camera_angle),
         camera_angle,
+        CameraInput::default(),
         rotation_controller,
         zoom_controller,
-        CameraPositionController::default(),
+        CameraPositionController::new(&config.camera, scene_path),
+        mode_controller,
+        CameraFollow::default(),
     ));
 }
@@ -1,4 +1,5 @@
 pub mod bundles;
 pub mod camera;
+pub mod platform;
 pub mod player;
 pub mod world;
@@ -0,0 +1,22 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::platform::{Platform, PlatformSpec};
+use crate::voxel::{VoxelCollider, VoxelGrid, Voxels};
+
+pub fn spawn_platform(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    spec: &PlatformSpec,
+) {
+    commands.spawn((
+        SceneRoot(asset_server.load(&spec.model)),
+        Voxels(asset_server.load::<VoxelGrid>(&spec.model)),
+        Transform::from_translation(spec.position).with_scale(Vec3::splat(0.05)),
+        Platform::new(spec.motion.clone()),
+        RigidBody::KinematicVelocityBased,
+        Velocity::default(),
+        ReadMassProperties::default(),
+        VoxelCollider,
+    ));
+}
@@ -2,22 +2,36 @@ use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
 use super::bundles::PlayerBundle;
-use crate::config::Config;
-use crate::physics::DynamicDamping;
-use crate::player::{GroundedState, Player};
+use crate::ability::grant_abilities;
+use crate::config::{AbilityConfig, Config};
+use crate::physics::{DynamicDamping, TranslationInterpolation};
+use crate::platform::PlatformCarry;
+use crate::player::{GroundedState, JumpTimers, Player, PlayerInput};
+use crate::respawn::{Checkpoint, Invulnerable};
+use crate::world::components::Level;
+
+/// Half the edge length of the player's cube.
+pub const PLAYER_HALF_SIZE: f32 = 0.25;
 
 pub fn spawn_player(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
     config: &Res<Config>,
+    level: &Level,
 ) {
-    let initial_player_pos = Vec3::new(0.0, 1.0, 0.0);
-    commands.spawn(PlayerBundle {
+    let initial_player_pos = level.spawn_position;
+    let mut player = commands.spawn(PlayerBundle {
         player: Player::new_with_config(&config.player),
+        player_input: PlayerInput::default(),
         grounded_state: GroundedState::default(),
-        dynamic_damping: DynamicDamping::default(),
-        mesh: Mesh3d(meshes.add(Mesh::from(Cuboid::new(0.5, 0.5, 0.5)))),
+        jump_timers: JumpTimers::default(),
+        platform_carry: PlatformCarry::default(),
+        checkpoint: Checkpoint::new(initial_player_pos),
+        invulnerable: Invulnerable::default(),
+        dynamic_damping: DynamicDamping::new_with_config(&config.player),
+        translation_interpolation: TranslationInterpolation::new(initial_player_pos),
+        mesh: Mesh3d(meshes.add(Mesh::from(Cuboid::from_length(PLAYER_HALF_SIZE * 2.0)))),
         material: MeshMaterial3d(materials.add(StandardMaterial {
             base_color: Color::srgb(0.8, 0.8, 0.9),
             metallic: 0.1,
@@ -31,9 +45,10 @@ pub fn spawn_player(
             initial_player_pos.z,
         ),
         rigid_body: RigidBody::Dynamic,
-        collider: Collider::cuboid(0.25, 0.25, 0.25),
+        collider: Collider::cuboid(PLAYER_HALF_SIZE, PLAYER_HALF_SIZE, PLAYER_HALF_SIZE),
         external_force: ExternalForce::default(),
         velocity: Velocity::default(),
+        mass_properties: ReadMassProperties::default(),
         restitution: Restitution::coefficient(0.0),
         friction: Friction::coefficient(0.3),
         damping: Damping {
@@ -43,4 +58,9 @@ pub fn spawn_player(
         locked_axes: LockedAxes::ROTATION_LOCKED,
         gravity_scale: GravityScale(1.0),
     });
+    grant_abilities(
+        &mut player,
+        &config.player.abilities,
+        &AbilityConfig::default(),
+    );
 }
@@ -1,15 +1,17 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
-pub fn spawn_room(commands: &mut Commands, asset_server: &Res<AssetServer>) {
+use crate::voxel::{VoxelCollider, VoxelGrid, Voxels};
+use crate::world::components::{Level, Room};
+
+pub fn spawn_room(commands: &mut Commands, asset_server: &Res<AssetServer>, level: &Level) {
     commands.spawn((
-        SceneRoot(asset_server.load("room.vox")),
+        Room,
+        SceneRoot(asset_server.load(&level.scene_path)),
+        Voxels(asset_server.load::<VoxelGrid>(&level.scene_path)),
         Transform::from_scale(Vec3::splat(0.05)),
         RigidBody::Fixed,
-        AsyncSceneCollider {
-            shape: Some(ComputedColliderShape::TriMesh(TriMeshFlags::empty())),
-            ..default()
-        },
+        VoxelCollider,
     ));
 }
 
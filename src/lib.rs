@@ -0,0 +1,67 @@
+//! Player controller, snapped isometric camera and debug overlay for voxel scenes.
+//!
+//! [`VoxelSampoPlugins`] expects [`DefaultPlugins`], `bevy_vox_scene::VoxScenePlugin` and
+//! `bevy_rapier3d::prelude::RapierPhysicsPlugin` to be added by the app, with Rapier running
+//! in `FixedUpdate` via `RapierPhysicsPlugin::in_fixed_schedule`.
+
+use bevy::{app::PluginGroupBuilder, prelude::*};
+
+pub mod ability;
+pub mod action;
+pub mod camera;
+pub mod config;
+pub mod cutaway;
+pub mod debug;
+pub mod entities;
+pub mod menu;
+pub mod physics;
+pub mod platform;
+pub mod player;
+pub mod replay;
+pub mod respawn;
+pub mod spawn;
+pub mod voxel;
+pub mod world;
+pub mod zone;
+
+use ability::AbilityPlugin;
+use action::ActionPlugin;
+use camera::CameraPlugin;
+use config::ConfigPlugin;
+use cutaway::CutawayPlugin;
+use debug::DebugPlugin;
+use menu::MenuPlugin;
+use physics::PhysicsPlugin;
+use platform::PlatformPlugin;
+use player::PlayerPlugin;
+use replay::ReplayPlugin;
+use respawn::RespawnPlugin;
+use spawn::SpawnPlugin;
+use voxel::VoxelPlugin;
+use world::WorldPlugin;
+use zone::ZonePlugin;
+
+/// All Voxel Sampo plugins. Each one can be configured with `set` or turned off with `disable`.
+pub struct VoxelSampoPlugins;
+
+impl PluginGroup for VoxelSampoPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(ConfigPlugin::default())
+            .add(ActionPlugin)
+            .add(PlayerPlugin)
+            .add(AbilityPlugin)
+            .add(PlatformPlugin)
+            .add(CameraPlugin)
+            .add(PhysicsPlugin)
+            .add(VoxelPlugin)
+            .add(ZonePlugin)
+            .add(RespawnPlugin)
+            .add(CutawayPlugin)
+            .add(WorldPlugin::default())
+            .add(DebugPlugin::default())
+            .add(MenuPlugin)
+            .add(SpawnPlugin)
+            .add(ReplayPlugin::default())
+    }
+}
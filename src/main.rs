@@ -1,26 +1,83 @@
-use bevy::{prelude::*, window::WindowResolution};
+use bevy::{
+    asset::io::AssetSourceBuilder,
+    prelude::*,
+    window::{WindowMode, WindowResolution},
+};
 use bevy_rapier3d::prelude::*;
 use bevy_vox_scene::VoxScenePlugin;
+use clap::Parser;
+use voxel_sampo::{
+    config::{Config, ConfigPlugin, UserSettings},
+    debug::DebugPlugin,
+    replay::{InputRecording, ReplayPlugin},
+    world::{components::Level, WorldPlugin},
+    VoxelSampoPlugins,
+};
 
-mod camera;
-mod config;
-mod debug;
-mod entities;
-mod physics;
-mod player;
-mod spawn;
-mod world;
+mod cli;
 
-use config::Config;
-use spawn::spawn_entities;
+use cli::{Cli, LEVEL_ASSET_SOURCE};
 
 fn main() {
+    let cli = Cli::parse();
+
+    let mut config = match Config::load_or_default(&cli.config) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("error: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    let settings = match UserSettings::load_or_default(&cli.settings) {
+        Ok(settings) => settings,
+        Err(err) => {
+            eprintln!("error: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    let replay = cli
+        .replay
+        .as_ref()
+        .map(|path| match InputRecording::load(path) {
+            Ok(recording) => recording,
+            Err(err) => {
+                eprintln!("error: {err}");
+                std::process::exit(1);
+            }
+        });
+
+    // A replay only reproduces the session from the same start and tick rate.
+    let spawn_position = match &replay {
+        Some(recording) => {
+            config.physics.tick_rate = recording.tick_rate;
+            recording.spawn_position
+        }
+        None => cli.spawn,
+    };
+
     let mut app = App::new();
 
+    if let Some(level_dir) = cli.level_source_dir() {
+        app.register_asset_source(
+            LEVEL_ASSET_SOURCE,
+            AssetSourceBuilder::platform_default(&level_dir.to_string_lossy(), None),
+        );
+    }
+
+    let window_mode = if cli.fullscreen {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+    } else {
+        WindowMode::Windowed
+    };
+
     let window_plugins = DefaultPlugins.set(WindowPlugin {
         primary_window: Some(Window {
             title: "Voxel Sampo".to_string(),
-            resolution: WindowResolution::new(960., 720.).with_scale_factor_override(1.),
+            resolution: WindowResolution::new(cli.width as f32, cli.height as f32)
+                .with_scale_factor_override(1.),
+            mode: window_mode,
             resizable: false,
             ..default()
         }),
@@ -28,23 +85,38 @@ fn main() {
     });
 
     let debug_render_plugin = RapierDebugRenderPlugin {
-        enabled: false,
+        enabled: cli.debug,
         ..default()
     };
 
+    let voxel_sampo_plugins = VoxelSampoPlugins
+        .set(ConfigPlugin {
+            config,
+            // A reload would replace the recorded tick rate, so replays don't hot-reload.
+            watch_path: replay.is_none().then(|| cli.config.clone()),
+            settings,
+            settings_path: Some(cli.settings.clone()),
+        })
+        .set(WorldPlugin {
+            level: Level {
+                scene_path: cli.level_asset_path(),
+                spawn_position,
+                ..default()
+            },
+        })
+        .set(DebugPlugin { enabled: cli.debug })
+        .set(ReplayPlugin {
+            record_path: cli.record.clone(),
+            replay,
+        });
+
     app.add_plugins((
         window_plugins,
         VoxScenePlugin::default(),
-        RapierPhysicsPlugin::<NoUserData>::default(),
+        RapierPhysicsPlugin::<NoUserData>::default().in_fixed_schedule(),
         debug_render_plugin,
-        player::plugin,
-        camera::plugin,
-        debug::plugin,
-        physics::plugin,
-        world::plugin,
-    ))
-    .init_resource::<Config>()
-    .add_systems(Startup, spawn_entities);
+        voxel_sampo_plugins,
+    ));
 
     app.run();
 }
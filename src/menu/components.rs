@@ -0,0 +1,33 @@
+use crate::action::Action;
+use bevy::prelude::*;
+
+/// Stick deflection that counts as pressing an axis while waiting for a new binding.
+pub const CAPTURE_AXIS_THRESHOLD: f32 = 0.5;
+
+#[derive(Resource, Debug, Default)]
+pub struct MenuState {
+    pub open: bool,
+    /// Action waiting for the next key, button or stick press.
+    pub capturing: Option<Action>,
+    /// Outcome of the last rebind, shown under the binding list.
+    pub status: String,
+}
+
+#[derive(Component)]
+pub struct SettingsMenu;
+
+/// Starts capturing a new binding for its action when clicked.
+#[derive(Component)]
+pub struct BindingButton(pub Action);
+
+#[derive(Component)]
+pub struct BindingText(pub Action);
+
+#[derive(Component)]
+pub struct ResetBindingsButton;
+
+#[derive(Component)]
+pub struct CloseMenuButton;
+
+#[derive(Component)]
+pub struct MenuStatusText;
@@ -0,0 +1,32 @@
+use super::components::*;
+use super::systems::*;
+use crate::action::{ActionState, ActionSystems};
+use crate::config::{settings::apply_user_settings_system, Config, UserSettings};
+use bevy::prelude::*;
+
+/// Settings screen for rebinding actions, opened with Escape or Start.
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionState>()
+            .init_resource::<UserSettings>()
+            .init_resource::<MenuState>()
+            .add_systems(Startup, setup_settings_menu)
+            .add_systems(PreUpdate, suppress_gameplay_actions.after(ActionSystems))
+            .add_systems(
+                Update,
+                (
+                    toggle_settings_menu,
+                    (capture_binding_system, menu_button_system)
+                        .chain()
+                        .before(apply_user_settings_system),
+                    toggle_menu_visibility.run_if(resource_changed::<MenuState>),
+                    update_binding_texts
+                        .after(apply_user_settings_system)
+                        .run_if(resource_changed::<MenuState>.or(resource_changed::<Config>)),
+                )
+                    .chain(),
+            );
+    }
+}
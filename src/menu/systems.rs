@@ -0,0 +1,326 @@
+use super::components::*;
+use crate::action::{Action, ActionState, AxisDirection, Binding};
+use crate::config::{Config, UserSettings};
+use bevy::prelude::*;
+
+const MENU_FONT_PATH: &str = "fonts/doto/Doto-VariableFont_ROND,wght.ttf";
+const BUTTON_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.1);
+const BUTTON_HOVER_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.25);
+const CONFLICT_COLOR: Color = Color::srgb(1.0, 0.35, 0.35);
+const CAPTURE_COLOR: Color = Color::srgb(1.0, 0.85, 0.3);
+
+const CAPTURE_AXES: [GamepadAxis; 4] = [
+    GamepadAxis::LeftStickX,
+    GamepadAxis::LeftStickY,
+    GamepadAxis::RightStickX,
+    GamepadAxis::RightStickY,
+];
+
+pub fn toggle_settings_menu(action_state: Res<ActionState>, mut menu_state: ResMut<MenuState>) {
+    // While capturing, the settings binding cancels the capture instead.
+    if menu_state.capturing.is_some() {
+        return;
+    }
+    if action_state.just_pressed(Action::ToggleSettings) {
+        menu_state.open = !menu_state.open;
+        menu_state.status.clear();
+    }
+}
+
+/// Releases every gameplay action and drops the scroll and pinch zoom while the menu is open, so
+/// the player and camera stand still.
+pub fn suppress_gameplay_actions(
+    menu_state: Res<MenuState>,
+    mut action_state: ResMut<ActionState>,
+) {
+    if !menu_state.open {
+        return;
+    }
+    for action in Action::ALL {
+        if action != Action::ToggleSettings {
+            action_state.release(action);
+        }
+    }
+    action_state.set_zoom(0.0);
+}
+
+pub fn capture_binding_system(
+    keyboard_input: Option<Res<ButtonInput<KeyCode>>>,
+    mouse_input: Option<Res<ButtonInput<MouseButton>>>,
+    gamepads: Query<&Gamepad>,
+    mut menu_state: ResMut<MenuState>,
+    mut settings: ResMut<UserSettings>,
+    config: Res<Config>,
+) {
+    let Some(action) = menu_state.capturing else {
+        return;
+    };
+    let Some(binding) =
+        just_pressed_binding(keyboard_input.as_deref(), mouse_input.as_deref(), &gamepads)
+    else {
+        return;
+    };
+    menu_state.capturing = None;
+
+    if config
+        .bindings
+        .get(Action::ToggleSettings)
+        .contains(&binding)
+    {
+        menu_state.status = format!("Kept the bindings of {}", action.label());
+        return;
+    }
+
+    // Applied to the config by `apply_user_settings_system`, which runs right after.
+    let mut bindings = config.bindings.clone();
+    let taken_from = settings.rebind(&mut bindings, action, binding);
+
+    menu_state.status = if taken_from.is_empty() {
+        format!("{} is now bound to {binding}", action.label())
+    } else {
+        let labels: Vec<_> = taken_from.iter().map(|other| other.label()).collect();
+        format!(
+            "{binding} moved to {} from {}",
+            action.label(),
+            labels.join(", ")
+        )
+    };
+}
+
+/// First key, mouse button, gamepad button or stick direction pressed this frame.
+fn just_pressed_binding(
+    keyboard_input: Option<&ButtonInput<KeyCode>>,
+    mouse_input: Option<&ButtonInput<MouseButton>>,
+    gamepads: &Query<&Gamepad>,
+) -> Option<Binding> {
+    if let Some(&key) = keyboard_input.and_then(|input| input.get_just_pressed().next()) {
+        return Some(Binding::Key(key));
+    }
+    // Left click stays with the menu so a misclick does not rebind anything.
+    if let Some(&button) = mouse_input.and_then(|input| {
+        input
+            .get_just_pressed()
+            .find(|&&button| button != MouseButton::Left)
+    }) {
+        return Some(Binding::Mouse(button));
+    }
+    for gamepad in gamepads.iter() {
+        if let Some(&button) = gamepad.get_just_pressed().next() {
+            return Some(Binding::GamepadButton(button));
+        }
+        for axis in CAPTURE_AXES {
+            let value = gamepad.get(axis).unwrap_or(0.0);
+            if value >= CAPTURE_AXIS_THRESHOLD {
+                return Some(Binding::GamepadAxis(axis, AxisDirection::Positive));
+            }
+            if value <= -CAPTURE_AXIS_THRESHOLD {
+                return Some(Binding::GamepadAxis(axis, AxisDirection::Negative));
+            }
+        }
+    }
+    None
+}
+
+type MenuButtonQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Interaction,
+        &'static mut BackgroundColor,
+        Option<&'static BindingButton>,
+        Has<ResetBindingsButton>,
+        Has<CloseMenuButton>,
+    ),
+    (Changed<Interaction>, With<Button>),
+>;
+
+pub fn menu_button_system(
+    mut button_query: MenuButtonQuery,
+    mut menu_state: ResMut<MenuState>,
+    mut settings: ResMut<UserSettings>,
+) {
+    for (interaction, mut background, binding_button, reset, close) in button_query.iter_mut() {
+        *background = match interaction {
+            Interaction::Hovered | Interaction::Pressed => BUTTON_HOVER_COLOR.into(),
+            Interaction::None => BUTTON_COLOR.into(),
+        };
+        if *interaction != Interaction::Pressed || !menu_state.open {
+            continue;
+        }
+
+        if let Some(BindingButton(action)) = binding_button {
+            menu_state.capturing = Some(*action);
+            menu_state.status = format!(
+                "Press a new input for {}, or Escape to cancel",
+                action.label()
+            );
+        } else if reset {
+            settings.bindings = None;
+            menu_state.capturing = None;
+            menu_state.status = "Restored the bindings from the config file".to_string();
+        } else if close {
+            menu_state.open = false;
+            menu_state.capturing = None;
+            menu_state.status.clear();
+        }
+    }
+}
+
+pub fn toggle_menu_visibility(
+    menu_state: Res<MenuState>,
+    mut menu_query: Query<&mut Visibility, With<SettingsMenu>>,
+) {
+    for mut visibility in menu_query.iter_mut() {
+        *visibility = if menu_state.open {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+pub fn update_binding_texts(
+    menu_state: Res<MenuState>,
+    config: Res<Config>,
+    mut binding_text_query: Query<(&BindingText, &mut Text, &mut TextColor)>,
+    mut status_text_query: Query<&mut Text, (With<MenuStatusText>, Without<BindingText>)>,
+) {
+    let conflicting = config.bindings.conflicting_actions();
+    for (BindingText(action), mut text, mut color) in binding_text_query.iter_mut() {
+        let bindings = config.bindings.get(*action);
+        let (label, text_color) = if menu_state.capturing == Some(*action) {
+            ("Press a new input...".to_string(), CAPTURE_COLOR)
+        } else if bindings.is_empty() {
+            ("Unbound".to_string(), CONFLICT_COLOR)
+        } else {
+            let label = bindings
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            if conflicting.contains(action) {
+                (format!("{label} (conflict)"), CONFLICT_COLOR)
+            } else {
+                (label, Color::WHITE)
+            }
+        };
+        **text = label;
+        color.0 = text_color;
+    }
+
+    for mut text in status_text_query.iter_mut() {
+        **text = menu_state.status.clone();
+    }
+}
+
+pub fn setup_settings_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load(MENU_FONT_PATH);
+    let text_font = |font_size: f32| TextFont {
+        font: font.clone(),
+        font_size,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            SettingsMenu,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            Visibility::Hidden,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::all(Val::Px(16.0)),
+                        row_gap: Val::Px(6.0),
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+                ))
+                .with_children(|panel| {
+                    panel.spawn((
+                        Text::new("Controls"),
+                        text_font(22.0),
+                        TextColor(Color::WHITE),
+                    ));
+
+                    for action in Action::ALL {
+                        panel
+                            .spawn(Node {
+                                justify_content: JustifyContent::SpaceBetween,
+                                align_items: AlignItems::Center,
+                                column_gap: Val::Px(24.0),
+                                ..default()
+                            })
+                            .with_children(|row| {
+                                row.spawn((
+                                    Text::new(action.label()),
+                                    text_font(14.0),
+                                    TextColor(Color::WHITE),
+                                ));
+                                row.spawn((
+                                    BindingButton(action),
+                                    Button,
+                                    Node {
+                                        min_width: Val::Px(280.0),
+                                        padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                                        ..default()
+                                    },
+                                    BackgroundColor(BUTTON_COLOR),
+                                ))
+                                .with_child((
+                                    BindingText(action),
+                                    Text::default(),
+                                    text_font(14.0),
+                                    TextColor(Color::WHITE),
+                                ));
+                            });
+                    }
+
+                    panel.spawn((
+                        MenuStatusText,
+                        Text::default(),
+                        text_font(14.0),
+                        TextColor(CAPTURE_COLOR),
+                    ));
+
+                    panel
+                        .spawn(Node {
+                            justify_content: JustifyContent::FlexEnd,
+                            column_gap: Val::Px(8.0),
+                            ..default()
+                        })
+                        .with_children(|row| {
+                            row.spawn((ResetBindingsButton, menu_button())).with_child((
+                                Text::new("Reset to defaults"),
+                                text_font(14.0),
+                                TextColor(Color::WHITE),
+                            ));
+                            row.spawn((CloseMenuButton, menu_button())).with_child((
+                                Text::new("Close"),
+                                text_font(14.0),
+                                TextColor(Color::WHITE),
+                            ));
+                        });
+                });
+        });
+}
+
+fn menu_button() -> impl Bundle {
+    (
+        Button,
+        Node {
+            padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+            ..default()
+        },
+        BackgroundColor(BUTTON_COLOR),
+    )
+}
@@ -1,3 +1,4 @@
+use crate::config::PlayerConfig;
 use bevy::prelude::*;
 
 #[derive(Component, Debug, Clone)]
@@ -6,11 +7,42 @@ pub struct DynamicDamping {
     pub air_damping: f32,
 }
 
+impl DynamicDamping {
+    pub fn new_with_config(player_config: &PlayerConfig) -> Self {
+        Self {
+            ground_damping: player_config.ground_damping,
+            air_damping: player_config.air_damping,
+        }
+    }
+
+    pub fn apply_config(&mut self, player_config: &PlayerConfig) {
+        *self = Self::new_with_config(player_config);
+    }
+}
+
 impl Default for DynamicDamping {
     fn default() -> Self {
+        Self::new_with_config(&PlayerConfig::default())
+    }
+}
+
+/// Physics-tick translations used to smooth the rendered [`Transform`] between ticks.
+#[derive(Component, Debug, Clone)]
+pub struct TranslationInterpolation {
+    pub previous: Vec3,
+    pub current: Vec3,
+}
+
+impl TranslationInterpolation {
+    pub fn new(translation: Vec3) -> Self {
         Self {
-            ground_damping: 8.0,
-            air_damping: 0.1,
+            previous: translation,
+            current: translation,
         }
     }
+
+    /// Moves to `translation` without interpolating from the old position.
+    pub fn teleport(&mut self, translation: Vec3) {
+        *self = Self::new(translation);
+    }
 }
@@ -1,17 +1,46 @@
-use bevy::prelude::*;
+use bevy::{app::RunFixedMainLoopSystem, prelude::*};
+use bevy_rapier3d::prelude::*;
 
 use super::systems::{
-    dynamic_damping_system, ground_detection_system, player_rotation_lock_system,
+    apply_damping_config_system, apply_tick_rate_system, dynamic_damping_system,
+    ground_detection_system, interpolate_physics_translation_system, player_rotation_lock_system,
+    record_physics_translation_system, restore_physics_translation_system,
 };
+use crate::config::Config;
 
-pub fn plugin(app: &mut App) {
-    app.add_systems(
-        Update,
-        (
-            ground_detection_system,
-            dynamic_damping_system,
-            player_rotation_lock_system,
-        )
-            .chain(),
-    );
+/// Grounded-ray detection and dynamic damping for the player body.
+///
+/// Runs on the fixed timestep; add Rapier with `RapierPhysicsPlugin::in_fixed_schedule`.
+#[derive(Default)]
+pub struct PhysicsPlugin;
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .add_systems(
+                PreUpdate,
+                apply_tick_rate_system.run_if(resource_changed::<Config>),
+            )
+            .add_systems(
+                RunFixedMainLoop,
+                (
+                    restore_physics_translation_system
+                        .in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop),
+                    interpolate_physics_translation_system
+                        .in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    apply_damping_config_system.run_if(resource_changed::<Config>),
+                    ground_detection_system,
+                    dynamic_damping_system,
+                    player_rotation_lock_system,
+                )
+                    .chain()
+                    .before(PhysicsSet::SyncBackend),
+            )
+            .add_systems(FixedPostUpdate, record_physics_translation_system);
+    }
 }
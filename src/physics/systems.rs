@@ -1,18 +1,25 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
-use super::components::DynamicDamping;
-use crate::player::{GroundedState, Player, RayInfo};
+use super::components::{DynamicDamping, TranslationInterpolation};
+use crate::config::Config;
+use crate::player::{GroundedState, JumpTimers, Player, RayInfo};
+use crate::voxel::{SurfaceMaterial, SurfaceTag, VoxelLookup};
 
 const GROUND_RAY_OFFSET_EPS: f32 = 2e-2;
 const GROUND_RAY_DISTANCE: f32 = 1e-1; // ε = 10cm
+/// How far below the player `GroundedState::ground_distance` is measured while airborne.
+const GROUND_PROBE_DISTANCE: f32 = 100.0;
 
 pub fn ground_detection_system(
-    mut query: Query<(Entity, &mut GroundedState, &Transform, &Collider), With<Player>>,
+    time: Res<Time>,
+    mut query: Query<(Entity, &Player, &mut GroundedState, &Transform, &Collider)>,
     rapier_context: ReadRapierContext,
+    voxel_lookup: VoxelLookup,
+    surfaces: Query<&SurfaceMaterial>,
 ) {
     if let Ok(context) = rapier_context.single() {
-        for (entity, mut grounded_state, transform, collider) in query.iter_mut() {
+        for (entity, player, mut grounded_state, transform, collider) in query.iter_mut() {
             let bottom_y = if let Some(cuboid) = collider.as_cuboid() {
                 transform.translation.y - cuboid.half_extents().y
             } else {
@@ -89,36 +96,112 @@ pub fn ground_detection_system(
             // Cast rays and collect results
             let mut rays = Vec::with_capacity(9);
             let mut hit_count = 0;
+            let mut walkable_normal = Vec3::ZERO;
+            let mut steep_normal = Vec3::ZERO;
+            // Nearest hit, preferring walkable ones; the centre ray wins ties.
+            let mut nearest: Option<(bool, Entity, RayIntersection)> = None;
+            let min_walkable_normal_y = player.max_slope_angle.to_radians().cos();
 
             for &ray_pos in &ray_positions {
-                let hit_result = context.cast_ray(ray_pos, ray_dir, max_toi, solid, filter);
-                let hit_point = hit_result.map(|(_, toi)| ray_pos + ray_dir * toi);
+                let hit_result =
+                    context.cast_ray_and_get_normal(ray_pos, ray_dir, max_toi, solid, filter);
 
-                if hit_result.is_some() {
+                if let Some((hit_entity, intersection)) = hit_result {
                     hit_count += 1;
+                    let walkable = intersection.normal.y >= min_walkable_normal_y;
+                    if walkable {
+                        walkable_normal += intersection.normal;
+                    } else {
+                        steep_normal += intersection.normal;
+                    }
+                    let closer = nearest.is_none_or(|(nearest_walkable, _, nearest)| {
+                        (walkable, -intersection.time_of_impact)
+                            > (nearest_walkable, -nearest.time_of_impact)
+                    });
+                    if closer {
+                        nearest = Some((walkable, hit_entity, intersection));
+                    }
                 }
 
                 rays.push(RayInfo {
                     origin: ray_pos,
                     direction: ray_dir,
                     distance: max_toi,
-                    hit: hit_point,
+                    hit: hit_result.map(|(_, intersection)| intersection.point),
+                    normal: hit_result.map(|(_, intersection)| intersection.normal),
                 });
             }
 
-            // Update grounded state
-            grounded_state.is_grounded = hit_count > 0;
+            // Any walkable hit counts as ground; a corner resting on a step edge should not slide.
+            let is_grounded = walkable_normal != Vec3::ZERO;
+            grounded_state.is_grounded = is_grounded;
+            grounded_state.on_steep_slope = !is_grounded && steep_normal != Vec3::ZERO;
+            grounded_state.ground_normal = if is_grounded {
+                walkable_normal.normalize()
+            } else {
+                steep_normal.try_normalize().unwrap_or(Vec3::Y)
+            };
             grounded_state.rays = rays;
             grounded_state.hit_count = hit_count;
+
+            grounded_state.ground_entity = nearest.map(|(_, hit_entity, _)| hit_entity);
+            grounded_state.ground_material = nearest.and_then(|(_, hit_entity, intersection)| {
+                voxel_lookup.surface_voxel(hit_entity, intersection.point, intersection.normal)
+            });
+            grounded_state.ground_surface = nearest.and_then(|(_, hit_entity, _)| {
+                surfaces.get(hit_entity).ok().map(|surface| surface.tag)
+            });
+            grounded_state.ground_distance = match nearest {
+                Some((_, _, intersection)) => {
+                    Some(intersection.time_of_impact - GROUND_RAY_OFFSET_EPS)
+                }
+                None => context
+                    .cast_ray(
+                        ray_positions[0],
+                        ray_dir,
+                        GROUND_PROBE_DISTANCE,
+                        solid,
+                        filter,
+                    )
+                    .map(|(_, toi)| toi - GROUND_RAY_OFFSET_EPS),
+            };
+
+            if is_grounded {
+                grounded_state.grounded_time += time.delta_secs();
+                grounded_state.airborne_time = 0.0;
+            } else {
+                grounded_state.airborne_time += time.delta_secs();
+                grounded_state.grounded_time = 0.0;
+            }
         }
     }
 }
 
+pub fn apply_damping_config_system(
+    config: Res<Config>,
+    mut query: Query<&mut DynamicDamping, With<Player>>,
+) {
+    for mut dynamic_damping in query.iter_mut() {
+        dynamic_damping.apply_config(&config.player);
+    }
+}
+
 pub fn dynamic_damping_system(
-    mut query: Query<(&GroundedState, &mut Damping, &DynamicDamping), With<Player>>,
+    mut query: Query<
+        (
+            &GroundedState,
+            Option<&JumpTimers>,
+            &mut Damping,
+            &DynamicDamping,
+        ),
+        With<Player>,
+    >,
 ) {
-    for (grounded_state, mut damping, dynamic_damping) in query.iter_mut() {
-        if grounded_state.is_grounded {
+    for (grounded_state, jump_timers, mut damping, dynamic_damping) in query.iter_mut() {
+        // The ground rays still reach the floor for a moment after take-off.
+        let jumping = jump_timers.is_some_and(|jump_timers| jump_timers.jumping);
+        let on_ice = grounded_state.ground_surface == Some(SurfaceTag::Ice);
+        if grounded_state.is_grounded && !jumping && !on_ice {
             damping.linear_damping = dynamic_damping.ground_damping;
         } else {
             damping.linear_damping = dynamic_damping.air_damping;
@@ -131,3 +214,47 @@ pub fn player_rotation_lock_system(mut player_query: Query<&mut Transform, With<
         transform.rotation = Quat::IDENTITY;
     }
 }
+
+pub fn apply_tick_rate_system(
+    config: Res<Config>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    timestep_mode: Option<ResMut<TimestepMode>>,
+) {
+    let tick_rate = config.physics.tick_rate;
+    fixed_time.set_timestep_hz(tick_rate);
+    if let Some(mut timestep_mode) = timestep_mode {
+        *timestep_mode = TimestepMode::Fixed {
+            dt: (1.0 / tick_rate) as f32,
+            substeps: 1,
+        };
+    }
+}
+
+/// Puts bodies back at their simulated position so Rapier doesn't see the
+/// interpolated [`Transform`] as a teleport.
+pub fn restore_physics_translation_system(
+    mut query: Query<(&TranslationInterpolation, &mut Transform)>,
+) {
+    for (interpolation, mut transform) in query.iter_mut() {
+        transform.translation = interpolation.current;
+    }
+}
+
+pub fn record_physics_translation_system(
+    mut query: Query<(&mut TranslationInterpolation, &Transform)>,
+) {
+    for (mut interpolation, transform) in query.iter_mut() {
+        interpolation.previous = interpolation.current;
+        interpolation.current = transform.translation;
+    }
+}
+
+pub fn interpolate_physics_translation_system(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&TranslationInterpolation, &mut Transform)>,
+) {
+    let alpha = fixed_time.overstep_fraction();
+    for (interpolation, mut transform) in query.iter_mut() {
+        transform.translation = interpolation.previous.lerp(interpolation.current, alpha);
+    }
+}
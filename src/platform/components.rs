@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How a [`Platform`] moves.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum PlatformMotion {
+    /// Travels between waypoints at `speed` m/s and waits `pause` seconds at each one.
+    ///
+    /// Waypoints are offsets from where the platform is spawned. After the last one it
+    /// returns to the first if `looping`, otherwise it retraces the path backwards.
+    Waypoints {
+        points: Vec<Vec3>,
+        speed: f32,
+        #[serde(default)]
+        pause: f32,
+        #[serde(default)]
+        looping: bool,
+    },
+    /// Spins about `axis` through its centre of mass at `speed` degrees per second.
+    Rotate { axis: Vec3, speed: f32 },
+}
+
+/// Kinematic body that moves on its own and carries the player standing on it.
+///
+/// Needs `RigidBody::KinematicVelocityBased` and a [`Velocity`](bevy_rapier3d::prelude::Velocity).
+#[derive(Component, Debug, Clone)]
+pub struct Platform {
+    pub motion: PlatformMotion,
+    /// Position the waypoints are relative to, taken on the first tick.
+    origin: Option<Vec3>,
+    /// Index of the waypoint being travelled to.
+    target: usize,
+    reverse: bool,
+    wait_remaining: f32,
+}
+
+impl Platform {
+    pub fn new(motion: PlatformMotion) -> Self {
+        Self {
+            motion,
+            origin: None,
+            target: 0,
+            reverse: false,
+            wait_remaining: 0.0,
+        }
+    }
+
+    /// Velocity that moves a platform at `position` along its path for one tick of length `dt`.
+    pub fn path_velocity(&mut self, position: Vec3, dt: f32) -> Vec3 {
+        let PlatformMotion::Waypoints {
+            points,
+            speed,
+            pause,
+            looping,
+        } = &self.motion
+        else {
+            return Vec3::ZERO;
+        };
+        if points.is_empty() || dt <= 0.0 {
+            return Vec3::ZERO;
+        }
+        let origin = match self.origin {
+            Some(origin) => origin,
+            None => {
+                // Don't pause at the first waypoint if the platform starts on it.
+                if points[0] == Vec3::ZERO {
+                    self.target = 1 % points.len();
+                }
+                *self.origin.insert(position)
+            }
+        };
+
+        if self.wait_remaining > 0.0 {
+            self.wait_remaining -= dt;
+            return Vec3::ZERO;
+        }
+
+        let to_target = origin + points[self.target.min(points.len() - 1)] - position;
+        let step = speed * dt;
+        if to_target.length() > step {
+            return to_target.normalize() * *speed;
+        }
+
+        // Arrive exactly this tick, then head for the next waypoint after the pause.
+        self.wait_remaining = *pause;
+        let last = points.len() - 1;
+        if *looping {
+            self.target = (self.target + 1) % points.len();
+        } else if last > 0 {
+            if self.target == last {
+                self.reverse = true;
+            } else if self.target == 0 {
+                self.reverse = false;
+            }
+            self.target = if self.reverse {
+                self.target - 1
+            } else {
+                self.target + 1
+            };
+        }
+        to_target / dt
+    }
+
+    /// Angular velocity in radians per second.
+    pub fn angular_velocity(&self) -> Vec3 {
+        match &self.motion {
+            PlatformMotion::Rotate { axis, speed } => axis.normalize_or_zero() * speed.to_radians(),
+            PlatformMotion::Waypoints { .. } => Vec3::ZERO,
+        }
+    }
+}
+
+/// Platform velocity added to the player's own, so it moves with the platform it stands on.
+///
+/// Horizontal velocity is kept after stepping or jumping off, until the player lands elsewhere.
+#[derive(Component, Debug, Clone, Default)]
+pub struct PlatformCarry {
+    /// Platform the player is standing on.
+    pub platform: Option<Entity>,
+    /// Velocity added this tick, on top of the player's own.
+    pub velocity: Vec3,
+}
+
+/// A platform for [`WorldPlugin`](crate::world::WorldPlugin) to spawn with the level.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlatformSpec {
+    /// .vox model rendered as the platform and used for its collider.
+    pub model: String,
+    pub position: Vec3,
+    pub motion: PlatformMotion,
+}
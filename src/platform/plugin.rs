@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::systems::{
+    apply_platform_velocity_system, platform_motion_system, remove_platform_velocity_system,
+};
+use crate::ability::systems::{air_jump_system, dash_system};
+use crate::physics::systems::dynamic_damping_system;
+use crate::player::systems::{player_input_system, player_velocity_limit_system, step_up_system};
+
+/// Moving and spinning platforms that carry the player.
+#[derive(Default)]
+pub struct PlatformPlugin;
+
+impl Plugin for PlatformPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                platform_motion_system.before(PhysicsSet::SyncBackend),
+                remove_platform_velocity_system
+                    .after(dynamic_damping_system)
+                    .before(air_jump_system)
+                    .before(player_velocity_limit_system),
+                apply_platform_velocity_system
+                    .after(platform_motion_system)
+                    .after(player_input_system)
+                    .after(step_up_system)
+                    .after(dash_system)
+                    .before(PhysicsSet::SyncBackend),
+            ),
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::components::{Platform, PlatformCarry};
+use crate::player::systems::DEFAULT_GRAVITY;
+use crate::player::{GroundedState, JumpTimers, Player};
+
+pub fn platform_motion_system(
+    time: Res<Time>,
+    mut query: Query<(&mut Platform, &Transform, &mut Velocity)>,
+) {
+    for (mut platform, transform, mut velocity) in query.iter_mut() {
+        velocity.linvel = platform.path_velocity(transform.translation, time.delta_secs());
+        velocity.angvel = platform.angular_velocity();
+    }
+}
+
+/// Takes last tick's platform velocity out of the player's, so movement, jump and speed
+/// limits act on the velocity relative to the platform.
+pub fn remove_platform_velocity_system(
+    mut query: Query<(&PlatformCarry, &mut Velocity), With<Player>>,
+) {
+    for (carry, mut velocity) in query.iter_mut() {
+        velocity.linvel -= carry.velocity;
+    }
+}
+
+type CarriedPlayerQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static GroundedState,
+        &'static JumpTimers,
+        &'static Transform,
+        &'static ExternalForce,
+        &'static ReadMassProperties,
+        &'static GravityScale,
+        &'static mut Damping,
+        &'static mut PlatformCarry,
+        &'static mut Velocity,
+    ),
+    With<Player>,
+>;
+
+type PlatformQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        &'static Velocity,
+        Option<&'static ReadMassProperties>,
+    ),
+    (With<Platform>, Without<Player>),
+>;
+
+/// Adds the velocity of the platform under the player back on top of its own.
+pub fn apply_platform_velocity_system(
+    time: Res<Time>,
+    mut player_query: CarriedPlayerQuery,
+    platform_query: PlatformQuery,
+    parents: Query<&ChildOf>,
+    rapier_config_query: Query<&RapierConfiguration>,
+) {
+    let dt = time.delta_secs();
+    let gravity = rapier_config_query
+        .single()
+        .map(|rapier_config| rapier_config.gravity)
+        .unwrap_or(Vec3::NEG_Y * DEFAULT_GRAVITY);
+    for (
+        grounded_state,
+        jump_timers,
+        transform,
+        external_force,
+        mass_properties,
+        gravity_scale,
+        mut damping,
+        mut carry,
+        mut velocity,
+    ) in player_query.iter_mut()
+    {
+        // Collider entities of a platform model are descendants of the platform body.
+        let platform = grounded_state
+            .ground_entity
+            .filter(|_| grounded_state.is_grounded && !jump_timers.jumping)
+            .and_then(|entity| {
+                std::iter::successors(Some(entity), |entity| {
+                    parents.get(*entity).ok().map(ChildOf::parent)
+                })
+                .find(|entity| platform_query.contains(*entity))
+            });
+
+        if let Some(platform) = platform {
+            let (platform_transform, platform_velocity, mass_properties) =
+                platform_query.get(platform).expect("platform found above");
+            let center = platform_transform.translation
+                + platform_transform.rotation
+                    * mass_properties
+                        .map(|mass_properties| mass_properties.get().local_center_of_mass)
+                        .unwrap_or_default();
+            // Follow the chord of the rotation rather than the tangent so spinning platforms
+            // don't fling the player outwards.
+            let arm = transform.translation - center;
+            let spin = Quat::from_scaled_axis(platform_velocity.angvel * dt);
+            let spin_velocity = if dt > 0.0 {
+                (spin * arm - arm) / dt
+            } else {
+                Vec3::ZERO
+            };
+            carry.platform = Some(platform);
+            carry.velocity = platform_velocity.linvel + spin_velocity;
+        } else if carry.platform.take().is_some() {
+            // Leaving a platform: vertical speed becomes the player's own so gravity acts on it,
+            // horizontal speed carries on until landing.
+            velocity.linvel.y += carry.velocity.y;
+            carry.velocity.y = 0.0;
+        } else if grounded_state.is_grounded {
+            carry.velocity = Vec3::ZERO;
+        }
+
+        if carry.velocity == Vec3::ZERO || damping.linear_damping == 0.0 {
+            continue;
+        }
+
+        // Rapier would damp the carried velocity too, and only after contacts are solved, so
+        // friction would see the player outrunning the platform. Damp the player's own velocity
+        // here instead, as Rapier would after this tick's forces, and turn Rapier's off.
+        let mass = mass_properties.get().mass;
+        let acceleration = if mass > 0.0 {
+            external_force.force / mass
+        } else {
+            Vec3::ZERO
+        } + gravity * gravity_scale.0;
+        let own = (velocity.linvel + acceleration * dt) / (1.0 + dt * damping.linear_damping)
+            - acceleration * dt;
+        velocity.linvel = own + carry.velocity;
+        damping.linear_damping = 0.0;
+    }
+}
@@ -1,12 +1,34 @@
 use crate::config::PlayerConfig;
+use crate::voxel::SurfaceTag;
 use bevy::prelude::*;
 
 #[derive(Component, Debug, Clone)]
 pub struct Player {
     pub move_force: f32,
     pub max_speed: f32,
-    pub jump_force: f32,
+    pub jump_height: f32,
+    pub time_to_apex: f32,
+    pub fall_gravity_multiplier: f32,
+    pub jump_cut: f32,
     pub air_control_force: f32,
+    pub snap_to_axis: bool,
+    pub step_height: f32,
+    pub max_slope_angle: f32,
+    pub coyote_time: f32,
+    pub jump_buffer_time: f32,
+}
+
+/// Input sampled every frame and consumed by the next physics tick.
+#[derive(Component, Debug, Clone, Default)]
+pub struct PlayerInput {
+    /// Camera-relative movement: `x` is right, `y` is forward. Analog input is shorter than 1.
+    pub movement: Vec2,
+    /// Set when jump is pressed; cleared once a tick has consumed it.
+    pub jump_requested: bool,
+    /// Whether jump is held; releasing it while rising cuts the jump short.
+    pub jump_held: bool,
+    /// Set when dash is pressed; cleared once a tick has consumed it.
+    pub dash_requested: bool,
 }
 
 #[derive(Component, Debug, Clone)]
@@ -15,19 +37,75 @@ pub struct RayInfo {
     pub direction: Vec3,
     pub distance: f32,
     pub hit: Option<Vec3>,
+    /// Surface normal at `hit`.
+    pub normal: Option<Vec3>,
 }
 
 #[derive(Component, Debug, Clone)]
 pub struct GroundedState {
+    /// Standing on a surface no steeper than the player's `max_slope_angle`.
     pub is_grounded: bool,
+    /// Touching only surfaces too steep to stand on; the player slides down them.
+    pub on_steep_slope: bool,
+    /// Average normal of the surfaces under the player, `Vec3::Y` when airborne.
+    pub ground_normal: Vec3,
+    /// Collider under the nearest ground ray hit.
+    pub ground_entity: Option<Entity>,
+    /// Height of the player's bottom above the surface below it, if there is one.
+    pub ground_distance: Option<f32>,
+    /// MagicaVoxel palette index of the voxel under the nearest ground ray hit.
+    pub ground_material: Option<u8>,
+    /// Tag of the [`SurfaceMaterial`](crate::voxel::SurfaceMaterial) under the nearest ground
+    /// ray hit, if that collider has one.
+    pub ground_surface: Option<SurfaceTag>,
+    /// Seconds since the player landed; 0 while airborne.
+    pub grounded_time: f32,
+    /// Seconds since the player left the ground; 0 while grounded.
+    pub airborne_time: f32,
     pub rays: Vec<RayInfo>,
     pub hit_count: usize,
 }
 
+/// Timing windows that forgive jumps pressed slightly too early or too late.
+#[derive(Component, Debug, Clone, Default)]
+pub struct JumpTimers {
+    /// Seconds left in which the player may still jump after leaving the ground.
+    pub coyote_remaining: f32,
+    /// Seconds left in which a jump pressed earlier is performed on landing.
+    pub buffer_remaining: f32,
+    /// Set by a jump until the player has left the ground, so it cannot jump again on the way up.
+    pub jumping: bool,
+    /// Set by a jump until it is cut short or starts to fall.
+    pub can_cut: bool,
+}
+
+impl JumpTimers {
+    /// Whether a jump pressed now would leave the ground, counting coyote time.
+    pub fn can_ground_jump(&self, grounded_state: &GroundedState) -> bool {
+        (grounded_state.is_grounded && !self.jumping) || self.coyote_remaining > 0.0
+    }
+
+    /// Records a jump that has just been launched, from the ground or in the air.
+    pub fn start_jump(&mut self) {
+        self.jumping = true;
+        self.can_cut = true;
+        self.coyote_remaining = 0.0;
+        self.buffer_remaining = 0.0;
+    }
+}
+
 impl Default for GroundedState {
     fn default() -> Self {
         Self {
             is_grounded: false,
+            on_steep_slope: false,
+            ground_normal: Vec3::Y,
+            ground_entity: None,
+            ground_distance: None,
+            ground_material: None,
+            ground_surface: None,
+            grounded_time: 0.0,
+            airborne_time: 0.0,
             rays: Vec::new(),
             hit_count: 0,
         }
@@ -39,10 +117,37 @@ impl Player {
         Self {
             move_force: player_config.move_force,
             max_speed: player_config.max_speed,
-            jump_force: player_config.jump_force,
+            jump_height: player_config.jump_height,
+            time_to_apex: player_config.time_to_apex,
+            fall_gravity_multiplier: player_config.fall_gravity_multiplier,
+            jump_cut: player_config.jump_cut,
             air_control_force: player_config.air_control_force,
+            snap_to_axis: player_config.snap_to_axis,
+            step_height: player_config.step_height,
+            max_slope_angle: player_config.max_slope_angle,
+            coyote_time: player_config.coyote_time,
+            jump_buffer_time: player_config.jump_buffer_time,
         }
     }
+
+    pub fn apply_config(&mut self, player_config: &PlayerConfig) {
+        *self = Self::new_with_config(player_config);
+    }
+
+    /// Downward acceleration that stops a jump exactly `time_to_apex` after take-off.
+    pub fn jump_gravity(&self) -> f32 {
+        2.0 * self.jump_height / (self.time_to_apex * self.time_to_apex)
+    }
+
+    /// Take-off speed that peaks at `jump_height` when stepped with ticks of length `dt`.
+    ///
+    /// Rapier applies gravity before moving the body, which loses `speed * dt / 2` of height
+    /// compared to the continuous arc, so the speed is solved for the stepped one.
+    pub fn jump_launch_speed(&self, dt: f32) -> f32 {
+        let gravity = self.jump_gravity();
+        let half_step = gravity * dt / 2.0;
+        half_step + (half_step * half_step + 2.0 * gravity * self.jump_height).sqrt()
+    }
 }
 
 impl Default for Player {
@@ -51,8 +156,16 @@ impl Default for Player {
         Self {
             move_force: default_config.move_force,
             max_speed: default_config.max_speed,
-            jump_force: default_config.jump_force,
+            jump_height: default_config.jump_height,
+            time_to_apex: default_config.time_to_apex,
+            fall_gravity_multiplier: default_config.fall_gravity_multiplier,
+            jump_cut: default_config.jump_cut,
             air_control_force: default_config.air_control_force,
+            snap_to_axis: default_config.snap_to_axis,
+            step_height: default_config.step_height,
+            max_slope_angle: default_config.max_slope_angle,
+            coyote_time: default_config.coyote_time,
+            jump_buffer_time: default_config.jump_buffer_time,
         }
     }
 }
@@ -1,6 +1,36 @@
-use super::systems::{player_input_system, player_velocity_limit_system};
-use bevy::prelude::*;
+use super::systems::{
+    apply_player_config_system, player_input_system, player_velocity_limit_system,
+    sample_player_input_system, step_up_system,
+};
+use crate::action::ActionState;
+use crate::config::Config;
+use crate::physics::systems::dynamic_damping_system;
+use bevy::{app::RunFixedMainLoopSystem, prelude::*};
+use bevy_rapier3d::prelude::*;
 
-pub fn plugin(app: &mut App) {
-    app.add_systems(Update, (player_input_system, player_velocity_limit_system));
+/// Keyboard-driven player controller. Input is sampled every frame and applied on the fixed timestep.
+#[derive(Default)]
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .init_resource::<ActionState>()
+            .add_systems(
+                RunFixedMainLoop,
+                sample_player_input_system.in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    apply_player_config_system.run_if(resource_changed::<Config>),
+                    player_velocity_limit_system,
+                    player_input_system,
+                    step_up_system,
+                )
+                    .chain()
+                    .after(dynamic_damping_system)
+                    .before(PhysicsSet::SyncBackend),
+            );
+    }
 }
@@ -1,64 +1,322 @@
-use super::components::{GroundedState, Player};
+use super::components::{GroundedState, JumpTimers, Player, PlayerInput};
+use crate::ability::grant_abilities;
+use crate::action::{Action, ActionState};
 use crate::camera::CameraAngle;
-use crate::config::Config;
+use crate::config::{AbilityConfig, Config};
+use crate::physics::DynamicDamping;
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
+/// Downward gravity assumed when Rapier's configuration can't be read.
+pub const DEFAULT_GRAVITY: f32 = 9.81;
+
+/// How far ahead of the player's side step-up looks for a ledge.
+const STEP_PROBE_DISTANCE: f32 = 5e-2;
+/// Height above the player's bottom at which ledges are probed.
+const STEP_PROBE_OFFSET: f32 = 1e-2;
+/// Gap left under the player after stepping up, so it settles onto the ledge.
+const STEP_CLEARANCE: f32 = 5e-3;
+/// Faces with a steeper upward normal than this are slopes rather than ledges.
+const STEP_WALL_MAX_NORMAL_Y: f32 = 0.3;
+
+pub fn sample_player_input_system(
+    action_state: Res<ActionState>,
+    mut player_query: Query<&mut PlayerInput, With<Player>>,
+) {
+    for mut player_input in player_query.iter_mut() {
+        player_input.movement = action_state.movement();
+        // Latch presses so frames without a physics tick don't drop them.
+        player_input.jump_requested |= action_state.just_pressed(Action::Jump);
+        player_input.jump_held = action_state.pressed(Action::Jump);
+        player_input.dash_requested |= action_state.just_pressed(Action::Dash);
+    }
+}
+
+#[allow(clippy::type_complexity)]
 pub fn player_input_system(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut player_query: Query<(&Player, &mut ExternalForce, &GroundedState), With<Player>>,
+    time: Res<Time>,
+    mut player_query: Query<
+        (
+            &Player,
+            &mut PlayerInput,
+            &mut JumpTimers,
+            &mut ExternalForce,
+            &GroundedState,
+            &mut Velocity,
+            &ReadMassProperties,
+            &mut Damping,
+            &DynamicDamping,
+            &mut GravityScale,
+        ),
+        With<Player>,
+    >,
     camera_query: Query<&CameraAngle>,
-    config: Res<Config>,
+    rapier_config_query: Query<&RapierConfiguration>,
 ) {
-    if let Ok((player, mut external_force, grounded_state)) = player_query.single_mut() {
-        let mut direction = Vec3::ZERO;
+    if let Ok((
+        player,
+        mut player_input,
+        mut jump_timers,
+        mut external_force,
+        grounded_state,
+        mut velocity,
+        mass_properties,
+        mut damping,
+        dynamic_damping,
+        mut gravity_scale,
+    )) = player_query.single_mut()
+    {
+        let direction = along_ground(
+            movement_direction(
+                player_input.movement,
+                camera_query.single().ok(),
+                player.snap_to_axis,
+            ),
+            grounded_state,
+        );
 
-        if let Ok(camera_angle) = camera_query.single() {
-            let forward = camera_angle.get_camera_forward_direction();
-            let right = camera_angle.get_camera_right_direction();
+        let mut force = Vec3::ZERO;
 
-            if keyboard_input.pressed(config.key_bindings.player_move_up) {
-                direction += forward;
-            }
-            if keyboard_input.pressed(config.key_bindings.player_move_down) {
-                direction -= forward;
-            }
-            if keyboard_input.pressed(config.key_bindings.player_move_left) {
-                direction -= right;
-            }
-            if keyboard_input.pressed(config.key_bindings.player_move_right) {
-                direction += right;
+        if direction != Vec3::ZERO {
+            // Keys always give full strength; a partly deflected stick walks slower.
+            let strength = player_input.movement.length().min(1.0);
+
+            if grounded_state.is_grounded {
+                force += direction * player.move_force * strength;
+            } else {
+                force += direction * player.air_control_force * strength;
             }
+
+            force = limit_force_to_max_speed(
+                force,
+                velocity.linvel,
+                player.max_speed * strength,
+                mass_properties.get().mass,
+                damping.linear_damping,
+                time.delta_secs(),
+            );
         }
 
-        direction.y = 0.0;
+        if player_input.jump_requested {
+            jump_timers.buffer_remaining = player.jump_buffer_time;
+        }
+        let jump_buffered = player_input.jump_requested || jump_timers.buffer_remaining > 0.0;
+        player_input.jump_requested = false;
 
-        let mut force = Vec3::ZERO;
+        if !grounded_state.is_grounded {
+            jump_timers.jumping = false;
+        }
+        let on_ground = grounded_state.is_grounded && !jump_timers.jumping;
+        if on_ground {
+            jump_timers.coyote_remaining = player.coyote_time;
+        }
 
-        if direction.length() > 0.0 {
-            if direction.x.abs() > direction.z.abs() {
-                direction = Vec3::new(direction.x.signum(), 0.0, 0.0);
-            } else {
-                direction = Vec3::new(0.0, 0.0, direction.z.signum());
-            }
+        let dt = time.delta_secs();
+        if jump_buffered && jump_timers.can_ground_jump(grounded_state) {
+            // Setting the speed rather than adding to it also makes late jumps off a ledge full height.
+            velocity.linvel.y = player.jump_launch_speed(dt);
+            // Ground damping would otherwise slow the first tick of the jump.
+            damping.linear_damping = dynamic_damping.air_damping;
+            jump_timers.start_jump();
+        }
 
-            if grounded_state.is_grounded {
-                force += direction * player.move_force;
+        if velocity.linvel.y <= 0.0 {
+            jump_timers.can_cut = false;
+        } else if jump_timers.can_cut && !player_input.jump_held {
+            velocity.linvel.y *= player.jump_cut;
+            jump_timers.can_cut = false;
+        }
+
+        let world_gravity = rapier_config_query
+            .single()
+            .map(|rapier_config| -rapier_config.gravity.y)
+            .unwrap_or(DEFAULT_GRAVITY);
+        if world_gravity > 0.0 {
+            // The jump curve only shapes airborne motion; on the ground, friction is tuned for
+            // normal gravity.
+            let scale = if grounded_state.is_grounded && !jump_timers.jumping {
+                1.0
+            } else if velocity.linvel.y > 0.0 {
+                player.jump_gravity() / world_gravity
             } else {
-                force += direction * player.air_control_force;
+                player.jump_gravity() * player.fall_gravity_multiplier / world_gravity
+            };
+            if gravity_scale.0 != scale {
+                gravity_scale.0 = scale;
             }
         }
 
-        if keyboard_input.just_pressed(config.key_bindings.player_jump)
-            && grounded_state.is_grounded
-        {
-            force.y += player.jump_force;
+        if !on_ground {
+            jump_timers.coyote_remaining = (jump_timers.coyote_remaining - dt).max(0.0);
         }
+        jump_timers.buffer_remaining = (jump_timers.buffer_remaining - dt).max(0.0);
 
         external_force.force = force;
     }
 }
 
+/// World-space unit direction for camera-relative `movement`, or zero without input.
+pub fn movement_direction(
+    movement: Vec2,
+    camera_angle: Option<&CameraAngle>,
+    snap_to_axis: bool,
+) -> Vec3 {
+    let mut direction = Vec3::ZERO;
+
+    if let Some(camera_angle) = camera_angle {
+        let forward = camera_angle.get_camera_forward_direction();
+        let right = camera_angle.get_camera_right_direction();
+
+        direction += forward * movement.y;
+        direction += right * movement.x;
+    }
+
+    direction.y = 0.0;
+
+    if direction.length() == 0.0 {
+        Vec3::ZERO
+    } else if !snap_to_axis {
+        direction.normalize()
+    } else if direction.x.abs() > direction.z.abs() {
+        Vec3::new(direction.x.signum(), 0.0, 0.0)
+    } else {
+        Vec3::new(0.0, 0.0, direction.z.signum())
+    }
+}
+
+/// Tilts `direction` to follow walkable ground, and drops any part of it pushing up a slope
+/// too steep to stand on.
+fn along_ground(direction: Vec3, grounded_state: &GroundedState) -> Vec3 {
+    let normal = grounded_state.ground_normal;
+    if grounded_state.is_grounded {
+        (direction - normal * direction.dot(normal)).normalize_or_zero()
+    } else if grounded_state.on_steep_slope {
+        let downhill = Vec3::new(normal.x, 0.0, normal.z).normalize_or_zero();
+        direction - downhill * direction.dot(downhill).min(0.0)
+    } else {
+        direction
+    }
+}
+
+/// Lifts a grounded player walking into a ledge no taller than `step_height` onto it.
+pub fn step_up_system(
+    mut player_query: Query<(
+        Entity,
+        &Player,
+        &PlayerInput,
+        &JumpTimers,
+        &GroundedState,
+        &Collider,
+        &mut Transform,
+    )>,
+    camera_query: Query<&CameraAngle>,
+    rapier_context: ReadRapierContext,
+) {
+    let Ok(context) = rapier_context.single() else {
+        return;
+    };
+    for (entity, player, player_input, jump_timers, grounded_state, collider, mut transform) in
+        player_query.iter_mut()
+    {
+        if !grounded_state.is_grounded || jump_timers.jumping || player.step_height <= 0.0 {
+            continue;
+        }
+        let direction = movement_direction(
+            player_input.movement,
+            camera_query.single().ok(),
+            player.snap_to_axis,
+        );
+        let Some(cuboid) = collider.as_cuboid() else {
+            continue;
+        };
+        if direction == Vec3::ZERO {
+            continue;
+        }
+
+        let half_extents = cuboid.half_extents();
+        let bottom = transform.translation - Vec3::Y * half_extents.y;
+        let side = Vec3::Y.cross(direction).normalize();
+        let reach = (half_extents * direction).length() + STEP_PROBE_DISTANCE;
+        let side_extent = (half_extents * side).length() * 0.8;
+        let filter = QueryFilter::default().exclude_collider(entity);
+
+        let mut rise: Option<f32> = None;
+        for offset in [-side_extent, 0.0, side_extent] {
+            let foot = bottom + side * offset;
+
+            // Something in the way at foot height...
+            let Some((_, blocked)) = context.cast_ray_and_get_normal(
+                foot + Vec3::Y * STEP_PROBE_OFFSET,
+                direction,
+                reach,
+                true,
+                filter,
+            ) else {
+                continue;
+            };
+            if blocked.normal.y.abs() > STEP_WALL_MAX_NORMAL_Y {
+                continue;
+            }
+
+            // ...with room above the step...
+            let above = foot + Vec3::Y * (player.step_height + STEP_PROBE_OFFSET);
+            if context
+                .cast_ray(above, direction, reach, true, filter)
+                .is_some()
+            {
+                rise = None;
+                break;
+            }
+
+            // ...and a top no higher than `step_height`.
+            let over_step = above + direction * (blocked.time_of_impact + STEP_PROBE_DISTANCE);
+            let Some((_, top_distance)) = context.cast_ray(
+                over_step,
+                Vec3::NEG_Y,
+                player.step_height + STEP_PROBE_OFFSET,
+                true,
+                filter,
+            ) else {
+                continue;
+            };
+            let step = player.step_height + STEP_PROBE_OFFSET - top_distance;
+            if step > 0.0 {
+                rise = Some(rise.map_or(step, |rise: f32| rise.max(step)));
+            }
+        }
+
+        if let Some(rise) = rise {
+            transform.translation.y += rise + STEP_CLEARANCE;
+        }
+    }
+}
+
+/// Scales down a horizontal force that would push the body past `max_speed` within one tick.
+fn limit_force_to_max_speed(
+    force: Vec3,
+    linvel: Vec3,
+    max_speed: f32,
+    mass: f32,
+    linear_damping: f32,
+    dt: f32,
+) -> Vec3 {
+    if mass <= 0.0 || dt <= 0.0 {
+        return force;
+    }
+
+    let horizontal_velocity = Vec3::new(linvel.x, 0.0, linvel.z);
+    let next_velocity = horizontal_velocity + force / mass * dt;
+    // Rapier applies damping after forces, so allow for the speed it will remove.
+    let speed_limit = (max_speed * (1.0 + dt * linear_damping)).max(horizontal_velocity.length());
+    if next_velocity.length() <= speed_limit {
+        return force;
+    }
+
+    let limited_velocity = next_velocity.clamp_length_max(speed_limit);
+    let limited_force = (limited_velocity - horizontal_velocity) * mass / dt;
+    Vec3::new(limited_force.x, force.y, limited_force.z)
+}
+
 pub fn player_velocity_limit_system(
     mut player_query: Query<(&Player, &mut Velocity), With<Player>>,
 ) {
@@ -71,3 +329,24 @@ pub fn player_velocity_limit_system(
         }
     }
 }
+
+/// Applies reloaded player settings, granting or taking away the abilities whose config changed
+/// since the last run; the first run only records them, as `spawn_player` already granted them.
+pub fn apply_player_config_system(
+    mut commands: Commands,
+    config: Res<Config>,
+    mut applied_abilities: Local<Option<AbilityConfig>>,
+    mut player_query: Query<(Entity, &mut Player)>,
+) {
+    let previous = applied_abilities.replace(config.player.abilities.clone());
+    for (entity, mut player) in player_query.iter_mut() {
+        player.apply_config(&config.player);
+        if let Some(previous) = &previous {
+            grant_abilities(
+                &mut commands.entity(entity),
+                &config.player.abilities,
+                previous,
+            );
+        }
+    }
+}
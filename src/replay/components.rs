@@ -0,0 +1,147 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use super::ReplayError;
+
+/// Largest distance between the replayed and recorded final position that still counts as a match.
+pub const REPLAY_POSITION_TOLERANCE: f32 = 1e-3;
+
+/// Input actions consumed by one physics tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TickInput {
+    /// Camera-relative movement: `x` is right, `y` is forward.
+    pub movement: Vec2,
+    pub jump: bool,
+    pub jump_held: bool,
+    pub dash: bool,
+    pub camera_rotate_clockwise: bool,
+    pub camera_rotate_counter_clockwise: bool,
+    /// Degrees of camera yaw (`x`) and pitch (`y`) dragged in a free camera mode.
+    pub camera_orbit: Vec2,
+    pub camera_cycle_mode: bool,
+}
+
+/// Per-tick input of a play session and where the player started and ended up.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    /// Physics ticks per second the input was recorded at.
+    pub tick_rate: f64,
+    /// Player position before the first recorded tick.
+    pub spawn_position: Vec3,
+    /// Player position after the last recorded tick.
+    pub final_position: Vec3,
+    pub ticks: Vec<TickInput>,
+}
+
+impl InputRecording {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|source| ReplayError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        ron::from_str(&contents).map_err(|source| ReplayError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Writes the recording as RON, one tick per line.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ReplayError> {
+        let path = path.as_ref();
+        let contents = ron::ser::to_string_pretty(self, PrettyConfig::new().depth_limit(2))?;
+        fs::write(path, contents).map_err(|source| ReplayError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// Seconds between writes of a recording in progress, so a crash loses at most this much.
+pub const RECORDING_FLUSH_INTERVAL_SECS: f32 = 1.0;
+
+/// Captures the input of every physics tick while present.
+#[derive(Resource, Debug)]
+pub struct InputRecorder {
+    /// File the recording is written to every [`RECORDING_FLUSH_INTERVAL_SECS`] and when the
+    /// app exits.
+    pub path: Option<PathBuf>,
+    pub recording: InputRecording,
+    /// Number of ticks in the file as last written.
+    pub saved_ticks: usize,
+    pub flush_timer: Timer,
+}
+
+impl Default for InputRecorder {
+    fn default() -> Self {
+        Self {
+            path: None,
+            recording: InputRecording::default(),
+            saved_ticks: 0,
+            flush_timer: Timer::from_seconds(RECORDING_FLUSH_INTERVAL_SECS, TimerMode::Repeating),
+        }
+    }
+}
+
+impl InputRecorder {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+            ..default()
+        }
+    }
+
+    /// Writes the ticks recorded so far, if there are any the file doesn't have yet.
+    pub fn flush(&mut self) -> Result<(), ReplayError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let ticks = self.recording.ticks.len();
+        if ticks != self.saved_ticks {
+            self.recording.save(path)?;
+            self.saved_ticks = ticks;
+        }
+        Ok(())
+    }
+}
+
+/// Feeds recorded input to the player and camera instead of the keyboard.
+#[derive(Resource, Debug)]
+pub struct InputReplay {
+    pub recording: InputRecording,
+    /// Index of the next tick to play back.
+    pub next_tick: usize,
+    pub tolerance: f32,
+    /// Set once every tick has been played back.
+    pub outcome: Option<ReplayOutcome>,
+}
+
+impl InputReplay {
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            recording,
+            next_tick: 0,
+            tolerance: REPLAY_POSITION_TOLERANCE,
+            outcome: None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_tick >= self.recording.ticks.len()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayOutcome {
+    pub final_position: Vec3,
+    pub expected_position: Vec3,
+    /// Whether the positions are within [`InputReplay::tolerance`] of each other.
+    pub matched: bool,
+}
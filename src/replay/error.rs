@@ -0,0 +1,21 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("failed to access recording {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to parse recording {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        #[source]
+        source: ron::error::SpannedError,
+    },
+    #[error("failed to serialize recording: {0}")]
+    Serialize(#[from] ron::Error),
+}
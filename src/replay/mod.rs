@@ -0,0 +1,8 @@
+pub mod components;
+mod error;
+pub mod plugin;
+pub mod systems;
+
+pub use components::*;
+pub use error::ReplayError;
+pub use plugin::ReplayPlugin;
@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+use super::components::{InputRecorder, InputRecording, InputReplay};
+use super::systems::{
+    check_replay_system, record_final_position_system, record_input_system, replay_input_system,
+    save_recording_system,
+};
+use crate::ability::systems::air_jump_system;
+use crate::camera::systems::camera_input_system;
+use crate::physics::systems::record_physics_translation_system;
+use crate::player::systems::player_input_system;
+
+/// Records the per-tick player and camera input to a file, or plays a recording back.
+#[derive(Default)]
+pub struct ReplayPlugin {
+    /// File to write the recorded input to while recording and when the app exits.
+    pub record_path: Option<PathBuf>,
+    /// Recording to play back instead of live input.
+    pub replay: Option<InputRecording>,
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(path) = &self.record_path {
+            app.insert_resource(InputRecorder::new(path));
+        }
+        if let Some(recording) = &self.replay {
+            app.insert_resource(InputReplay::new(recording.clone()));
+        }
+
+        app.add_systems(
+            FixedUpdate,
+            (
+                replay_input_system.run_if(resource_exists::<InputReplay>),
+                record_input_system.run_if(resource_exists::<InputRecorder>),
+            )
+                .chain()
+                .before(camera_input_system)
+                .before(air_jump_system)
+                .before(player_input_system),
+        )
+        .add_systems(
+            FixedPostUpdate,
+            (
+                record_final_position_system.run_if(resource_exists::<InputRecorder>),
+                check_replay_system.run_if(resource_exists::<InputReplay>),
+            )
+                .after(record_physics_translation_system),
+        )
+        .add_systems(
+            Last,
+            save_recording_system.run_if(resource_exists::<InputRecorder>),
+        );
+    }
+}
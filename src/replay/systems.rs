@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+
+use super::components::{InputRecorder, InputReplay, ReplayOutcome, TickInput};
+use crate::camera::CameraInput;
+use crate::physics::TranslationInterpolation;
+use crate::player::{Player, PlayerInput};
+
+pub fn replay_input_system(
+    fixed_time: Res<Time<Fixed>>,
+    mut replay: ResMut<InputReplay>,
+    mut player_query: Query<&mut PlayerInput, With<Player>>,
+    mut camera_query: Query<&mut CameraInput>,
+) {
+    // Ticks before the player exists were not recorded either.
+    let Ok(mut player_input) = player_query.single_mut() else {
+        return;
+    };
+    let Some(&tick) = replay.recording.ticks.get(replay.next_tick) else {
+        return;
+    };
+
+    if replay.next_tick == 0 {
+        let tick_rate = 1.0 / fixed_time.timestep().as_secs_f64();
+        if (tick_rate - replay.recording.tick_rate).abs() > 1e-6 {
+            warn!(
+                "Replaying input recorded at {} ticks/s at {tick_rate} ticks/s; the result will differ",
+                replay.recording.tick_rate
+            );
+        }
+    }
+
+    player_input.movement = tick.movement;
+    player_input.jump_requested = tick.jump;
+    player_input.jump_held = tick.jump_held;
+    player_input.dash_requested = tick.dash;
+    for mut camera_input in camera_query.iter_mut() {
+        camera_input.rotate_clockwise = tick.camera_rotate_clockwise;
+        camera_input.rotate_counter_clockwise = tick.camera_rotate_counter_clockwise;
+        camera_input.orbit = tick.camera_orbit;
+        camera_input.cycle_mode_requested = tick.camera_cycle_mode;
+    }
+    replay.next_tick += 1;
+}
+
+pub fn record_input_system(
+    fixed_time: Res<Time<Fixed>>,
+    mut recorder: ResMut<InputRecorder>,
+    player_query: Query<(&PlayerInput, &TranslationInterpolation), With<Player>>,
+    camera_query: Query<&CameraInput>,
+) {
+    let Ok((player_input, interpolation)) = player_query.single() else {
+        return;
+    };
+    let camera_input = camera_query.single().cloned().unwrap_or_default();
+
+    let recording = &mut recorder.recording;
+    if recording.ticks.is_empty() {
+        recording.tick_rate = 1.0 / fixed_time.timestep().as_secs_f64();
+        recording.spawn_position = interpolation.current;
+    }
+    recording.ticks.push(TickInput {
+        movement: player_input.movement,
+        jump: player_input.jump_requested,
+        jump_held: player_input.jump_held,
+        dash: player_input.dash_requested,
+        camera_rotate_clockwise: camera_input.rotate_clockwise,
+        camera_rotate_counter_clockwise: camera_input.rotate_counter_clockwise,
+        camera_orbit: camera_input.orbit,
+        camera_cycle_mode: camera_input.cycle_mode_requested,
+    });
+}
+
+pub fn record_final_position_system(
+    mut recorder: ResMut<InputRecorder>,
+    player_query: Query<&TranslationInterpolation, With<Player>>,
+) {
+    if let Ok(interpolation) = player_query.single() {
+        recorder.recording.final_position = interpolation.current;
+    }
+}
+
+pub fn check_replay_system(
+    mut replay: ResMut<InputReplay>,
+    player_query: Query<&TranslationInterpolation, With<Player>>,
+) {
+    if replay.outcome.is_some() || !replay.is_finished() {
+        return;
+    }
+    let Ok(interpolation) = player_query.single() else {
+        return;
+    };
+
+    let final_position = interpolation.current;
+    let expected_position = replay.recording.final_position;
+    let distance = final_position.distance(expected_position);
+    let matched = distance <= replay.tolerance;
+    if matched {
+        info!("Replay finished at {final_position}, matching the recording");
+    } else {
+        error!(
+            "Replay finished at {final_position}, {distance} away from the recorded {expected_position}"
+        );
+    }
+
+    replay.outcome = Some(ReplayOutcome {
+        final_position,
+        expected_position,
+        matched,
+    });
+}
+
+/// Writes the recording out periodically while recording, so a crash loses little of it, and
+/// once more when the app exits.
+pub fn save_recording_system(
+    time: Res<Time<Real>>,
+    mut exit_events: EventReader<AppExit>,
+    mut recorder: ResMut<InputRecorder>,
+) {
+    let exiting = exit_events.read().next().is_some();
+    if !recorder.flush_timer.tick(time.delta()).just_finished() && !exiting {
+        return;
+    }
+    match recorder.flush() {
+        Ok(()) if exiting => {
+            if let Some(path) = &recorder.path {
+                info!(
+                    "Saved {} recorded ticks to {}",
+                    recorder.recording.ticks.len(),
+                    path.display()
+                );
+            }
+        }
+        Ok(()) => {}
+        Err(err) => error!("{err}"),
+    }
+}
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+
+/// Where the player is put back after falling out of the world or touching something deadly.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    pub position: Vec3,
+    /// Zone that set this checkpoint; `None` for the level's spawn position.
+    pub zone: Option<String>,
+}
+
+impl Checkpoint {
+    pub fn new(position: Vec3) -> Self {
+        Self {
+            position,
+            zone: None,
+        }
+    }
+}
+
+/// Seconds left after a respawn during which kill zones and lava are ignored.
+#[derive(Component, Debug, Clone, Default)]
+pub struct Invulnerable {
+    pub remaining: f32,
+}
+
+impl Invulnerable {
+    pub fn is_active(&self) -> bool {
+        self.remaining > 0.0
+    }
+}
+
+/// Full-screen overlay that fades back to the game after a respawn.
+#[derive(Component, Debug, Clone, Default)]
+pub struct RespawnFade {
+    /// Seconds left until the overlay is fully transparent.
+    pub remaining: f32,
+}
+
+/// Why the player was sent back to its [`Checkpoint`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RespawnCause {
+    /// Fell below `Config::respawn.kill_height`.
+    FellOut,
+    /// Entered a zone listed in `Config::respawn.kill_zones`.
+    KillZone(String),
+    /// Landed on a surface tagged `Lava`.
+    Lava,
+}
+
+/// The player was moved back to its checkpoint.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct PlayerRespawned {
+    pub player: Entity,
+    pub position: Vec3,
+    pub cause: RespawnCause,
+}
+
+/// The player entered a checkpoint zone, making it the new respawn point.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct CheckpointActivated {
+    pub player: Entity,
+    pub zone: String,
+    pub position: Vec3,
+}
@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+
+use super::components::{CheckpointActivated, PlayerRespawned};
+use super::systems::{
+    checkpoint_system, invulnerability_system, respawn_fade_system, respawn_system,
+    setup_respawn_fade, snap_camera_on_respawn_system,
+};
+use crate::camera::systems::camera_follow_system;
+use crate::config::Config;
+use crate::zone::systems::zone_event_system;
+
+/// Checkpoints and out-of-world recovery: moves the player back to its last checkpoint after
+/// a fall or a deadly surface, then fades the screen in and briefly makes it invulnerable.
+#[derive(Default)]
+pub struct RespawnPlugin;
+
+impl Plugin for RespawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .add_event::<CheckpointActivated>()
+            .add_event::<PlayerRespawned>()
+            .add_systems(Startup, setup_respawn_fade)
+            .add_systems(
+                FixedUpdate,
+                (invulnerability_system, checkpoint_system, respawn_system)
+                    .chain()
+                    .after(zone_event_system),
+            )
+            .add_systems(
+                Update,
+                (
+                    respawn_fade_system,
+                    snap_camera_on_respawn_system.before(camera_follow_system),
+                ),
+            );
+    }
+}
@@ -0,0 +1,190 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::components::{
+    Checkpoint, CheckpointActivated, Invulnerable, PlayerRespawned, RespawnCause, RespawnFade,
+};
+use crate::ability::Dash;
+use crate::camera::CameraFollow;
+use crate::config::Config;
+use crate::physics::TranslationInterpolation;
+use crate::platform::PlatformCarry;
+use crate::player::{GroundedState, JumpTimers, Player};
+use crate::voxel::SurfaceTag;
+use crate::zone::PlayerEnteredZone;
+
+/// Makes the spot where the player enters a checkpoint zone its new respawn point.
+pub fn checkpoint_system(
+    config: Res<Config>,
+    mut entered: EventReader<PlayerEnteredZone>,
+    mut activated: EventWriter<CheckpointActivated>,
+    mut query: Query<(&Transform, &mut Checkpoint), With<Player>>,
+) {
+    for event in entered.read() {
+        if !config.respawn.is_checkpoint_zone(&event.zone) {
+            continue;
+        }
+        let Ok((transform, mut checkpoint)) = query.get_mut(event.player) else {
+            continue;
+        };
+        // Walking back through the current checkpoint keeps the spot it was first entered at.
+        if checkpoint.zone.as_deref() == Some(event.zone.as_str()) {
+            continue;
+        }
+        let position = transform.translation;
+        *checkpoint = Checkpoint {
+            position,
+            zone: Some(event.zone.clone()),
+        };
+        activated.write(CheckpointActivated {
+            player: event.player,
+            zone: event.zone.clone(),
+            position,
+        });
+    }
+}
+
+/// Sends the player back to its [`Checkpoint`] when it falls out of the world, enters a kill
+/// zone or lands on lava, clearing everything that would carry its old motion over.
+#[allow(clippy::type_complexity)]
+pub fn respawn_system(
+    config: Res<Config>,
+    mut entered: EventReader<PlayerEnteredZone>,
+    mut respawned: EventWriter<PlayerRespawned>,
+    mut query: Query<
+        (
+            Entity,
+            &Checkpoint,
+            &mut Invulnerable,
+            &mut Transform,
+            &mut TranslationInterpolation,
+            &mut Velocity,
+            &mut ExternalForce,
+            &mut GroundedState,
+            &mut JumpTimers,
+            &mut PlatformCarry,
+            Option<&mut Dash>,
+        ),
+        With<Player>,
+    >,
+) {
+    let respawn = &config.respawn;
+    let kill_zones: Vec<_> = entered
+        .read()
+        .filter(|event| respawn.is_kill_zone(&event.zone))
+        .collect();
+    for (
+        entity,
+        checkpoint,
+        mut invulnerable,
+        mut transform,
+        mut interpolation,
+        mut velocity,
+        mut external_force,
+        mut grounded_state,
+        mut jump_timers,
+        mut platform_carry,
+        dash,
+    ) in query.iter_mut()
+    {
+        let cause = if transform.translation.y < respawn.kill_height {
+            // Invulnerability can't save a player that has left the level.
+            Some(RespawnCause::FellOut)
+        } else if invulnerable.is_active() {
+            None
+        } else if let Some(event) = kill_zones.iter().find(|event| event.player == entity) {
+            Some(RespawnCause::KillZone(event.zone.clone()))
+        } else if grounded_state.is_grounded
+            && grounded_state.ground_surface == Some(SurfaceTag::Lava)
+        {
+            Some(RespawnCause::Lava)
+        } else {
+            None
+        };
+        let Some(cause) = cause else {
+            continue;
+        };
+
+        transform.translation = checkpoint.position;
+        interpolation.teleport(checkpoint.position);
+        *velocity = Velocity::zero();
+        *external_force = ExternalForce::default();
+        *grounded_state = GroundedState::default();
+        *jump_timers = JumpTimers::default();
+        *platform_carry = PlatformCarry::default();
+        if let Some(mut dash) = dash {
+            dash.active = None;
+        }
+        invulnerable.remaining = respawn.invulnerability_time;
+        respawned.write(PlayerRespawned {
+            player: entity,
+            position: checkpoint.position,
+            cause,
+        });
+    }
+}
+
+pub fn invulnerability_system(time: Res<Time>, mut query: Query<&mut Invulnerable>) {
+    let dt = time.delta_secs();
+    for mut invulnerable in query.iter_mut() {
+        if invulnerable.is_active() {
+            invulnerable.remaining = (invulnerable.remaining - dt).max(0.0);
+        }
+    }
+}
+
+/// Spawns the full-screen overlay that [`respawn_fade_system`] fades out after a respawn.
+pub fn setup_respawn_fade(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        BackgroundColor(Color::NONE),
+        // Below the menu and debug overlay, which are spawned at the default global z-index.
+        GlobalZIndex(-1),
+        RespawnFade::default(),
+    ));
+}
+
+/// Blacks the screen out on a respawn and fades it back in over `Config::respawn.fade_time`.
+pub fn respawn_fade_system(
+    time: Res<Time>,
+    config: Res<Config>,
+    mut respawned: EventReader<PlayerRespawned>,
+    mut query: Query<(&mut RespawnFade, &mut BackgroundColor)>,
+) {
+    let fade_time = config.respawn.fade_time;
+    let restart = respawned.read().count() > 0;
+    for (mut fade, mut background) in query.iter_mut() {
+        if restart {
+            fade.remaining = fade_time;
+        } else if fade.remaining > 0.0 {
+            fade.remaining = (fade.remaining - time.delta_secs()).max(0.0);
+        } else {
+            continue;
+        }
+        let alpha = if fade_time > 0.0 {
+            fade.remaining / fade_time
+        } else {
+            0.0
+        };
+        background.0 = Color::BLACK.with_alpha(alpha);
+    }
+}
+
+/// Centres the camera on a respawned player at once; it is somewhere else entirely, so the
+/// camera shouldn't pan across the level to it.
+pub fn snap_camera_on_respawn_system(
+    mut respawned: EventReader<PlayerRespawned>,
+    mut cameras: Query<&mut CameraFollow>,
+) {
+    let Some(event) = respawned.read().last() else {
+        return;
+    };
+    for mut follow in cameras.iter_mut() {
+        follow.snap(event.position);
+    }
+}
@@ -2,6 +2,19 @@ use bevy::prelude::*;
 
 use crate::config::Config;
 use crate::entities::{camera::spawn_camera, player::spawn_player};
+use crate::world::components::Level;
+
+/// Spawns the player and camera at startup. Disable it to spawn your own.
+#[derive(Default)]
+pub struct SpawnPlugin;
+
+impl Plugin for SpawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .init_resource::<Level>()
+            .add_systems(Startup, spawn_entities);
+    }
+}
 
 pub fn spawn_entities(
     mut commands: Commands,
@@ -9,7 +22,8 @@ pub fn spawn_entities(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     config: Res<Config>,
+    level: Res<Level>,
 ) {
-    spawn_player(&mut commands, &mut meshes, &mut materials, &config);
-    spawn_camera(&mut commands, &config);
+    spawn_player(&mut commands, &mut meshes, &mut materials, &config, &level);
+    spawn_camera(&mut commands, &config, &level);
 }
@@ -0,0 +1,270 @@
+use std::collections::{BTreeMap, HashMap};
+
+use bevy::{math::Affine3A, prelude::*};
+use dot_vox::{DotVoxData, Frame, Model, SceneNode};
+
+/// Prefix of node and layer names whose models are trigger zones rather than geometry.
+pub const ZONE_PREFIX: &str = "zone:";
+
+/// Palette index of every voxel in a scene, in Bevy's Y-up axes.
+///
+/// Cells are one unit wide in the local space of the entity holding the grid's [`Voxels`], so
+/// cell `c` covers `c..c + 1` on each axis.
+#[derive(Asset, TypePath, Debug, Clone, Default, PartialEq)]
+pub struct VoxelGrid {
+    /// Lowest cell of the grid's bounding box.
+    pub origin: IVec3,
+    pub size: UVec3,
+    /// Palette index per cell, `x` fastest and `y` slowest; 0 is empty.
+    pub(super) cells: Vec<u8>,
+    /// MagicaVoxel material type per palette index that has one, without the leading
+    /// underscore: `diffuse`, `metal`, `glass`, `emit`, `blend` or `media`.
+    pub material_types: HashMap<u8, String>,
+    /// Voxels of the models marked as zones, by zone name; they are left out of this grid.
+    pub zones: BTreeMap<String, VoxelGrid>,
+}
+
+impl VoxelGrid {
+    /// Builds the smallest grid holding every `(cell, palette index)` pair.
+    pub fn from_voxels(voxels: impl IntoIterator<Item = (IVec3, u8)>) -> Self {
+        let voxels: Vec<_> = voxels
+            .into_iter()
+            .filter(|(_, index)| *index != 0)
+            .collect();
+        let Some(min) = voxels.iter().map(|(cell, _)| *cell).reduce(IVec3::min) else {
+            return Self::default();
+        };
+        let max = voxels
+            .iter()
+            .map(|(cell, _)| *cell)
+            .reduce(IVec3::max)
+            .unwrap_or(min);
+        let size = (max - min + IVec3::ONE).as_uvec3();
+        let mut grid = Self {
+            origin: min,
+            size,
+            cells: vec![0; (size.x * size.y * size.z) as usize],
+            material_types: HashMap::new(),
+            zones: BTreeMap::new(),
+        };
+        for (cell, index) in voxels {
+            let i = grid.index(cell).expect("cell inside the bounding box");
+            grid.cells[i] = index;
+        }
+        grid
+    }
+
+    /// Places the file's models where the scene graph puts them, matching the axes and
+    /// centring `bevy_vox_scene` renders them with: MagicaVoxel `(x, y, z)` becomes
+    /// `(-x, z, y)` and each model is centred on its node's translation and turned by its
+    /// rotation.
+    ///
+    /// Models on a node or layer named `zone:<name>` go into [`VoxelGrid::zones`] instead.
+    pub fn from_vox(file: &DotVoxData) -> Self {
+        let mut instances = Vec::new();
+        if file.scenes.is_empty() {
+            // Files without a scene graph place every model at the origin.
+            instances.extend(file.models.iter().map(|model| ModelInstance {
+                model,
+                transform: Affine3A::IDENTITY,
+                zone: None,
+            }));
+        } else {
+            collect_instances(file, 0, Affine3A::IDENTITY, None, None, &mut instances);
+        }
+
+        let mut zones: BTreeMap<String, Vec<_>> = BTreeMap::new();
+        for instance in &instances {
+            if let Some(zone) = instance.zone {
+                zones
+                    .entry(zone.to_string())
+                    .or_default()
+                    .extend(instance.voxels());
+            }
+        }
+        let grid = Self::from_voxels(
+            instances
+                .iter()
+                .filter(|instance| instance.zone.is_none())
+                .flat_map(ModelInstance::voxels),
+        );
+        Self {
+            material_types: file
+                .materials
+                .iter()
+                .filter_map(|material| {
+                    let index = u8::try_from(material.id).ok()?;
+                    let kind = material.material_type()?;
+                    Some((index, kind.trim_start_matches('_').to_string()))
+                })
+                .collect(),
+            zones: zones
+                .into_iter()
+                .map(|(zone, voxels)| (zone, Self::from_voxels(voxels)))
+                .collect(),
+            ..grid
+        }
+    }
+
+    /// MagicaVoxel material type of palette entry `index`, if the file gave it one.
+    pub fn material_type(&self, index: u8) -> Option<&str> {
+        self.material_types.get(&index).map(String::as_str)
+    }
+
+    /// Palette index of the voxel at `cell`, or `None` if it is empty or outside the grid.
+    pub fn get(&self, cell: IVec3) -> Option<u8> {
+        self.index(cell)
+            .map(|i| self.cells[i])
+            .filter(|index| *index != 0)
+    }
+
+    /// Every filled cell and its palette index.
+    pub fn iter(&self) -> impl Iterator<Item = (IVec3, u8)> + '_ {
+        let size = self.size.as_ivec3();
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, index)| **index != 0)
+            .map(move |(i, index)| {
+                let i = i as i32;
+                let offset = IVec3::new(i % size.x, i / (size.x * size.z), (i / size.x) % size.z);
+                (self.origin + offset, *index)
+            })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().all(|index| *index == 0)
+    }
+
+    fn index(&self, cell: IVec3) -> Option<usize> {
+        let offset = cell - self.origin;
+        let size = self.size.as_ivec3();
+        if offset.cmplt(IVec3::ZERO).any() || offset.cmpge(size).any() {
+            return None;
+        }
+        Some(((offset.y * size.z + offset.z) * size.x + offset.x) as usize)
+    }
+}
+
+/// A model placed in the scene by a shape node.
+struct ModelInstance<'a> {
+    model: &'a Model,
+    /// Places the model's centre in the scene, in Bevy's Y-up axes.
+    transform: Affine3A,
+    /// Zone named by the model's transform node or its layer.
+    zone: Option<&'a str>,
+}
+
+impl<'a> ModelInstance<'a> {
+    /// Cell and palette index of each voxel, in the grid's Y-up axes.
+    fn voxels(&self) -> impl Iterator<Item = (IVec3, u8)> + 'a {
+        let size = self.model.size;
+        let centre = (UVec3::new(size.x, size.y, size.z) / 2).as_vec3();
+        let transform = self.transform;
+        self.model.voxels.iter().map(move |voxel| {
+            // Middle of the voxel relative to the model's centre; quarter turns keep it in the
+            // middle of a cell.
+            let local = Vec3::new(voxel.x.into(), voxel.y.into(), voxel.z.into()) + 0.5 - centre;
+            let position = transform.transform_point3(Vec3::new(-local.x, local.z, local.y));
+            // dot_vox counts palette entries from 0; the file and MagicaVoxel from 1.
+            (position.floor().as_ivec3(), voxel.i + 1)
+        })
+    }
+}
+
+/// Walks the scene graph from `node`; the nearest transform node above a shape names it and
+/// places it on a layer.
+fn collect_instances<'a>(
+    file: &'a DotVoxData,
+    node: u32,
+    transform: Affine3A,
+    name: Option<&'a str>,
+    layer: Option<&'a str>,
+    instances: &mut Vec<ModelInstance<'a>>,
+) {
+    match file.scenes.get(node as usize) {
+        Some(SceneNode::Transform {
+            attributes,
+            frames,
+            child,
+            layer_id,
+        }) => {
+            // Like `bevy_vox_scene`, which leaves the root node's transform out of the scene.
+            let local = match frames.first() {
+                Some(frame) if node != 0 => frame_transform(frame),
+                _ => Affine3A::IDENTITY,
+            };
+            let layer_name = file
+                .layers
+                .get(*layer_id as usize)
+                .and_then(|layer| layer.attributes.get("_name"));
+            collect_instances(
+                file,
+                *child,
+                transform * local,
+                attributes.get("_name").map(String::as_str).or(name),
+                layer_name.map(String::as_str).or(layer),
+                instances,
+            );
+        }
+        Some(SceneNode::Group { children, .. }) => {
+            for child in children {
+                collect_instances(file, *child, transform, name, layer, instances);
+            }
+        }
+        Some(SceneNode::Shape { models, .. }) => {
+            for shape_model in models {
+                let Some(model) = file.models.get(shape_model.model_id as usize) else {
+                    warn!(
+                        "shape node {node} refers to missing model {}",
+                        shape_model.model_id
+                    );
+                    continue;
+                };
+                instances.push(ModelInstance {
+                    model,
+                    transform,
+                    zone: [name, layer]
+                        .into_iter()
+                        .flatten()
+                        .find_map(|name| name.strip_prefix(ZONE_PREFIX)),
+                });
+            }
+        }
+        None => {}
+    }
+}
+
+/// Translation and rotation of a transform node's first frame in Bevy's axes, worked out the
+/// way `bevy_vox_scene` places the rendered model.
+fn frame_transform(frame: &Frame) -> Affine3A {
+    let translation = frame.position().map_or(Vec3::ZERO, |position| {
+        Vec3::new(-position.x as f32, position.z as f32, position.y as f32)
+    });
+    let rotation = frame.orientation().map_or(Mat3::IDENTITY, |orientation| {
+        let (rotation, flip) = orientation.to_quat_scale();
+        let (axis, angle) = Quat::from_array(rotation).to_axis_angle();
+        let flip = Vec3::from(flip);
+        Mat3::from_axis_angle(Vec3::new(-axis.x, axis.z, axis.y), angle)
+            * Mat3::from_diagonal(Vec3::new(flip.x, flip.z, flip.y))
+    });
+    Affine3A::from_mat3_translation(rotation, translation)
+}
+
+/// Voxel data of the scene rendered on this entity, for looking up what the player touches.
+#[derive(Component, Debug, Clone, Default)]
+pub struct Voxels(pub Handle<VoxelGrid>);
+
+/// Builds this entity's collider from its [`Voxels`], merging voxels into as few boxes as it can.
+///
+/// Unlike a triangle mesh of the rendered faces, the boxes have no internal edges for the
+/// player to catch on. Voxels of each [`SurfaceMaterial`](super::SurfaceMaterial) go into a
+/// separate child collider marked [`VoxelColliderPart`], as configured in `Config::surfaces`,
+/// and each [`Zone`](crate::zone::Zone) becomes a sensor.
+#[derive(Component, Debug, Clone, Default)]
+#[require(Voxels)]
+pub struct VoxelCollider;
+
+/// Child collider holding the boxes of one surface material or zone of a [`VoxelCollider`].
+#[derive(Component, Debug, Clone, Default)]
+pub struct VoxelColliderPart;
@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+
+use super::components::VoxelGrid;
+
+/// An axis-aligned block of voxels sharing the same key, in grid cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoxelBox<K> {
+    pub min: IVec3,
+    pub size: UVec3,
+    pub key: K,
+}
+
+impl<K> VoxelBox<K> {
+    /// Centre and half extents in the grid's local space.
+    pub fn center_and_half_extents(&self) -> (Vec3, Vec3) {
+        let half_extents = self.size.as_vec3() / 2.0;
+        (self.min.as_vec3() + half_extents, half_extents)
+    }
+}
+
+impl VoxelGrid {
+    /// Covers the filled voxels with as few boxes as greedy merging finds, never merging
+    /// voxels whose palette indices map to different keys.
+    ///
+    /// Boxes grow along `x`, then `z`, then `y`, and don't overlap.
+    pub fn greedy_boxes<K: Copy + PartialEq>(&self, key: impl Fn(u8) -> K) -> Vec<VoxelBox<K>> {
+        let size = self.size.as_ivec3();
+        let mut keys: Vec<Option<K>> = self
+            .cells
+            .iter()
+            .map(|index| (*index != 0).then(|| key(*index)))
+            .collect();
+        let at = |cell: IVec3| ((cell.y * size.z + cell.z) * size.x + cell.x) as usize;
+
+        let mut boxes = Vec::new();
+        for y in 0..size.y {
+            for z in 0..size.z {
+                for x in 0..size.x {
+                    let start = IVec3::new(x, y, z);
+                    let Some(key) = keys[at(start)] else {
+                        continue;
+                    };
+                    let matches = |keys: &[Option<K>], cell: IVec3| keys[at(cell)] == Some(key);
+
+                    let mut extent = IVec3::ONE;
+                    while start.x + extent.x < size.x && matches(&keys, start + IVec3::X * extent.x)
+                    {
+                        extent.x += 1;
+                    }
+                    while start.z + extent.z < size.z
+                        && (0..extent.x)
+                            .all(|dx| matches(&keys, start + IVec3::new(dx, 0, extent.z)))
+                    {
+                        extent.z += 1;
+                    }
+                    while start.y + extent.y < size.y
+                        && (0..extent.x).all(|dx| {
+                            (0..extent.z)
+                                .all(|dz| matches(&keys, start + IVec3::new(dx, extent.y, dz)))
+                        })
+                    {
+                        extent.y += 1;
+                    }
+
+                    for dy in 0..extent.y {
+                        for dz in 0..extent.z {
+                            for dx in 0..extent.x {
+                                keys[at(start + IVec3::new(dx, dy, dz))] = None;
+                            }
+                        }
+                    }
+                    boxes.push(VoxelBox {
+                        min: self.origin + start,
+                        size: extent.as_uvec3(),
+                        key,
+                    });
+                }
+            }
+        }
+        boxes
+    }
+}
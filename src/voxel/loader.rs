@@ -0,0 +1,31 @@
+use bevy::asset::{io::Reader, AssetLoader, LoadContext};
+use bevy::prelude::*;
+
+use super::components::VoxelGrid;
+
+/// Loads `.vox` files as a [`VoxelGrid`]. Request the asset type explicitly, as
+/// `bevy_vox_scene` loads the same extension as a scene.
+#[derive(Default, TypePath)]
+pub struct VoxelGridLoader;
+
+impl AssetLoader for VoxelGridLoader {
+    type Asset = VoxelGrid;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<VoxelGrid, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file = dot_vox::load_bytes(&bytes)?;
+        Ok(VoxelGrid::from_vox(&file))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["vox"]
+    }
+}
@@ -0,0 +1,37 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use super::components::{VoxelGrid, Voxels};
+
+/// Finds the voxel behind a collider surface, for colliders built from a [`Voxels`] scene.
+#[derive(SystemParam)]
+pub struct VoxelLookup<'w, 's> {
+    grids: Option<Res<'w, Assets<VoxelGrid>>>,
+    voxels: Query<'w, 's, (&'static Voxels, &'static GlobalTransform)>,
+    parents: Query<'w, 's, &'static ChildOf>,
+}
+
+impl VoxelLookup<'_, '_> {
+    /// Palette index of the voxel whose face at `point` has the outward `normal`.
+    ///
+    /// `collider` may be the entity holding the [`Voxels`] or any of its descendants.
+    pub fn surface_voxel(&self, collider: Entity, point: Vec3, normal: Vec3) -> Option<u8> {
+        let grids = self.grids.as_ref()?;
+        let (voxels, transform) = self.find_voxels(collider)?;
+        let grid = grids.get(&voxels.0)?;
+        let to_local = transform.affine().inverse();
+        let local_normal = to_local.transform_vector3(normal).normalize_or_zero();
+        // Half a cell inwards from the face lands in the middle of the voxel.
+        let local_point = to_local.transform_point3(point) - local_normal * 0.5;
+        grid.get(local_point.floor().as_ivec3())
+    }
+
+    fn find_voxels(&self, entity: Entity) -> Option<(&Voxels, &GlobalTransform)> {
+        let mut entity = entity;
+        loop {
+            if let Ok(found) = self.voxels.get(entity) {
+                return Some(found);
+            }
+            entity = self.parents.get(entity).ok()?.parent();
+        }
+    }
+}
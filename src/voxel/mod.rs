@@ -0,0 +1,13 @@
+pub mod components;
+pub mod greedy;
+pub mod loader;
+pub mod lookup;
+pub mod plugin;
+pub mod surface;
+pub mod systems;
+
+pub use components::*;
+pub use greedy::VoxelBox;
+pub use lookup::VoxelLookup;
+pub use plugin::VoxelPlugin;
+pub use surface::{SurfaceMaterial, SurfaceTag};
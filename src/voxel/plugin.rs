@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+
+use super::components::VoxelGrid;
+use super::loader::VoxelGridLoader;
+use super::systems::{build_voxel_colliders_system, level_bounds_system};
+use crate::camera::systems::camera_follow_system;
+use crate::config::Config;
+
+/// Reads voxel data from `.vox` files alongside the rendered scene and builds colliders and the
+/// camera's [`LevelBounds`](crate::camera::LevelBounds) from it.
+#[derive(Default)]
+pub struct VoxelPlugin;
+
+impl Plugin for VoxelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .init_asset::<VoxelGrid>()
+            .init_asset_loader::<VoxelGridLoader>()
+            .add_systems(
+                Update,
+                (
+                    build_voxel_colliders_system,
+                    level_bounds_system.before(camera_follow_system),
+                ),
+            );
+    }
+}
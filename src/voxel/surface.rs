@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// What a surface means to gameplay, beyond how it pushes back.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SurfaceTag {
+    #[default]
+    Solid,
+    /// The player keeps sliding: ground damping is not applied.
+    Ice,
+    Bouncy,
+    Lava,
+    Water,
+}
+
+/// Contact properties of a collider built from voxels, set per palette entry.
+///
+/// Friction combines with the player's by taking the lower value and restitution by taking
+/// the higher, so a surface can be made slippery or bouncy on its own.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SurfaceMaterial {
+    pub friction: f32,
+    pub restitution: f32,
+    pub tag: SurfaceTag,
+}
+
+impl SurfaceMaterial {
+    pub fn friction(&self) -> Friction {
+        Friction {
+            coefficient: self.friction,
+            combine_rule: CoefficientCombineRule::Min,
+        }
+    }
+
+    pub fn restitution(&self) -> Restitution {
+        Restitution {
+            coefficient: self.restitution,
+            combine_rule: CoefficientCombineRule::Max,
+        }
+    }
+}
+
+impl Default for SurfaceMaterial {
+    fn default() -> Self {
+        Self {
+            friction: 0.5,
+            restitution: 0.0,
+            tag: SurfaceTag::Solid,
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::components::{VoxelCollider, VoxelColliderPart, VoxelGrid, Voxels};
+use super::greedy::VoxelBox;
+use super::surface::SurfaceMaterial;
+use crate::camera::LevelBounds;
+use crate::config::{Config, SurfaceConfig, ZoneConfig};
+use crate::world::components::Room;
+use crate::zone::Zone;
+
+/// Compound of one cuboid per box, in the grid's local space.
+pub fn box_collider<K>(boxes: &[VoxelBox<K>]) -> Collider {
+    Collider::compound(
+        boxes
+            .iter()
+            .map(|voxel_box| {
+                let (center, half_extents) = voxel_box.center_and_half_extents();
+                (
+                    center,
+                    Quat::IDENTITY,
+                    Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+                )
+            })
+            .collect(),
+    )
+}
+
+/// What the voxels in a merged box become.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Part<'a> {
+    Surface(SurfaceMaterial),
+    Zone(&'a str),
+}
+
+/// Gives [`VoxelCollider`] entities one child collider per surface material and one sensor per
+/// zone once their grid has loaded, and rebuilds them when the file or `Config::surfaces` or
+/// `Config::zones` changes.
+pub fn build_voxel_colliders_system(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<VoxelGrid>>,
+    mut built_config: Local<Option<(SurfaceConfig, ZoneConfig)>>,
+    grids: Res<Assets<VoxelGrid>>,
+    config: Res<Config>,
+    query: Query<(Entity, &Voxels), With<VoxelCollider>>,
+    parts: Query<(Entity, &ChildOf), With<VoxelColliderPart>>,
+) {
+    let modified: Vec<_> = asset_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    let config_changed = built_config
+        .as_ref()
+        .is_none_or(|(surfaces, zones)| *surfaces != config.surfaces || *zones != config.zones);
+    if config_changed {
+        *built_config = Some((config.surfaces.clone(), config.zones.clone()));
+    }
+
+    for (entity, voxels) in query.iter() {
+        let built: Vec<_> = parts
+            .iter()
+            .filter(|(_, child_of)| child_of.parent() == entity)
+            .map(|(part, _)| part)
+            .collect();
+        if !built.is_empty() && !config_changed && !modified.contains(&voxels.0.id()) {
+            continue;
+        }
+        let Some(grid) = grids.get(&voxels.0) else {
+            continue;
+        };
+        for part in built {
+            commands.entity(part).despawn();
+        }
+
+        let mut boxes = grid.greedy_boxes(|index| match config.zones.palette.get(&index) {
+            Some(zone) => Part::Zone(zone),
+            None => Part::Surface(config.surfaces.resolve(index, grid.material_type(index))),
+        });
+        for (zone, zone_grid) in &grid.zones {
+            boxes.extend(zone_grid.greedy_boxes(|_| Part::Zone(zone)));
+        }
+        let mut by_part: Vec<(Part, Vec<VoxelBox<Part>>)> = Vec::new();
+        for voxel_box in boxes {
+            match by_part.iter_mut().find(|(part, _)| *part == voxel_box.key) {
+                Some((_, boxes)) => boxes.push(voxel_box),
+                None => by_part.push((voxel_box.key, vec![voxel_box])),
+            }
+        }
+
+        for (part, boxes) in by_part {
+            let mut part_entity = commands.spawn((
+                VoxelColliderPart,
+                ChildOf(entity),
+                Transform::default(),
+                box_collider(&boxes),
+            ));
+            match part {
+                Part::Surface(surface) => {
+                    part_entity.insert((surface, surface.friction(), surface.restitution()));
+                }
+                Part::Zone(zone) => {
+                    part_entity.insert((
+                        Zone {
+                            name: zone.to_string(),
+                        },
+                        Sensor,
+                        ActiveEvents::COLLISION_EVENTS,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Keeps [`LevelBounds`] up to date with the bounding box of the level's voxels.
+pub fn level_bounds_system(
+    mut commands: Commands,
+    bounds: Option<Res<LevelBounds>>,
+    grids: Res<Assets<VoxelGrid>>,
+    rooms: Query<(&Voxels, &GlobalTransform), With<Room>>,
+) {
+    let Some((grid, transform)) = rooms
+        .iter()
+        .find_map(|(voxels, transform)| Some((grids.get(&voxels.0)?, transform)))
+        .filter(|(grid, _)| !grid.is_empty())
+    else {
+        return;
+    };
+    let min = grid.origin.as_vec3();
+    let max = min + grid.size.as_vec3();
+    let corners = [min, max].map(|corner| transform.transform_point(corner));
+    let level_bounds = LevelBounds {
+        min: corners[0].min(corners[1]),
+        max: corners[0].max(corners[1]),
+    };
+    if bounds.is_none_or(|bounds| *bounds != level_bounds) {
+        commands.insert_resource(level_bounds);
+    }
+}
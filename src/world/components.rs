@@ -1,5 +1,9 @@
 use bevy::prelude::*;
 
+use crate::platform::PlatformSpec;
+
+pub const DEFAULT_LEVEL_PATH: &str = "room.vox";
+
 #[derive(Component, Debug)]
 pub struct Room;
 
@@ -8,3 +12,20 @@ pub struct EnvironmentLight;
 
 #[derive(Component, Debug)]
 pub struct DirectionalLightSource;
+
+#[derive(Resource, Debug, Clone)]
+pub struct Level {
+    pub scene_path: String,
+    pub spawn_position: Vec3,
+    pub platforms: Vec<PlatformSpec>,
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Self {
+            scene_path: DEFAULT_LEVEL_PATH.to_string(),
+            spawn_position: Vec3::new(0.0, 1.0, 0.0),
+            platforms: Vec::new(),
+        }
+    }
+}
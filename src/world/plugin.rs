@@ -1,7 +1,19 @@
 use bevy::prelude::*;
 
+use super::components::Level;
 use super::systems::setup_world;
+use crate::config::Config;
 
-pub fn plugin(app: &mut App) {
-    app.add_systems(Startup, setup_world);
+/// Loads the level scene, its moving platforms and lighting.
+#[derive(Default)]
+pub struct WorldPlugin {
+    pub level: Level,
+}
+
+impl Plugin for WorldPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .insert_resource(self.level.clone())
+            .add_systems(Startup, setup_world);
+    }
 }
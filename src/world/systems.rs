@@ -1,8 +1,19 @@
 use bevy::prelude::*;
 
+use super::components::Level;
+use crate::config::Config;
+use crate::entities::platform::spawn_platform;
 use crate::entities::world::{spawn_lighting, spawn_room};
 
-pub fn setup_world(mut commands: Commands, asset_server: Res<AssetServer>) {
-    spawn_room(&mut commands, &asset_server);
+pub fn setup_world(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    level: Res<Level>,
+    config: Res<Config>,
+) {
+    spawn_room(&mut commands, &asset_server, &level);
+    for platform in level.platforms.iter().chain(&config.platforms) {
+        spawn_platform(&mut commands, &asset_server, platform);
+    }
     spawn_lighting(&mut commands, &asset_server);
 }
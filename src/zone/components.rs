@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+
+/// Sensor volume authored in a level's .vox file, named after the zone it marks out.
+///
+/// Spawned by [`VoxelCollider`](crate::voxel::VoxelCollider) for models on a node or layer
+/// named `zone:<name>`, and for palette indices listed in `Config::zones`.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Zone {
+    pub name: String,
+}
+
+/// The player started overlapping a [`Zone`].
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct PlayerEnteredZone {
+    pub player: Entity,
+    pub zone: String,
+}
+
+/// The player stopped overlapping a [`Zone`].
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct PlayerExitedZone {
+    pub player: Entity,
+    pub zone: String,
+}
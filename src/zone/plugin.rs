@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+
+use super::components::{PlayerEnteredZone, PlayerExitedZone};
+use super::systems::{hide_zone_models_system, hide_zone_palette_voxels_system, zone_event_system};
+use crate::config::Config;
+
+/// Trigger zones authored in .vox files or reserved palette indices: hides them and reports the
+/// player entering and leaving them with [`PlayerEnteredZone`] and [`PlayerExitedZone`].
+#[derive(Default)]
+pub struct ZonePlugin;
+
+impl Plugin for ZonePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Config>()
+            .add_event::<PlayerEnteredZone>()
+            .add_event::<PlayerExitedZone>()
+            .add_systems(
+                Update,
+                (hide_zone_models_system, hide_zone_palette_voxels_system),
+            )
+            // Rapier steps in `FixedPostUpdate`, so this reads the contacts of the previous tick.
+            .add_systems(FixedUpdate, zone_event_system);
+    }
+}
@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use bevy_vox_scene::{
+    modify_voxel_model, Voxel, VoxelLayer, VoxelModelInstance, VoxelModifier, VoxelRegionMode,
+};
+
+use super::components::{PlayerEnteredZone, PlayerExitedZone, Zone};
+use crate::config::Config;
+use crate::player::Player;
+use crate::voxel::ZONE_PREFIX;
+
+/// Turns Rapier's sensor events between the player and a [`Zone`] into zone events.
+pub fn zone_event_system(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut entered: EventWriter<PlayerEnteredZone>,
+    mut exited: EventWriter<PlayerExitedZone>,
+    players: Query<(), With<Player>>,
+    zones: Query<&Zone>,
+) {
+    for event in collision_events.read() {
+        let (CollisionEvent::Started(first, second, _) | CollisionEvent::Stopped(first, second, _)) =
+            *event;
+        let Some((player, zone)) = [(first, second), (second, first)]
+            .into_iter()
+            .find(|(player, _)| players.contains(*player))
+            .and_then(|(player, other)| Some((player, zones.get(other).ok()?)))
+        else {
+            continue;
+        };
+        let zone = zone.name.clone();
+        match event {
+            CollisionEvent::Started(..) => {
+                entered.write(PlayerEnteredZone { player, zone });
+            }
+            CollisionEvent::Stopped(..) => {
+                exited.write(PlayerExitedZone { player, zone });
+            }
+        }
+    }
+}
+
+/// Hides the rendered models of zones, which are on nodes or layers named `zone:<name>`.
+#[allow(clippy::type_complexity)]
+pub fn hide_zone_models_system(
+    mut query: Query<
+        (&mut Visibility, Option<&Name>, Option<&VoxelLayer>),
+        Or<(Added<Name>, Added<VoxelLayer>)>,
+    >,
+) {
+    for (mut visibility, name, layer) in query.iter_mut() {
+        // Scene entities are named by their path through named groups, like `group/zone:exit`.
+        let node_name = name.and_then(|name| name.as_str().rsplit('/').next());
+        let layer_name = layer.and_then(|layer| layer.name.as_deref());
+        if [node_name, layer_name]
+            .into_iter()
+            .flatten()
+            .any(|name| name.starts_with(ZONE_PREFIX))
+        {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+/// Empties the rendered voxels of the palette indices reserved in `Config::zones`, once for each
+/// newly spawned model and for every model when the config changes.
+///
+/// Voxels of an index that is no longer reserved come back only when the level is reloaded.
+pub fn hide_zone_palette_voxels_system(
+    mut commands: Commands,
+    config: Res<Config>,
+    query: Query<(Ref<VoxelModelInstance>, &Mesh3d)>,
+) {
+    if config.zones.palette.is_empty() {
+        return;
+    }
+    let mut modified = Vec::new();
+    for (instance, mesh) in query.iter() {
+        let model = instance.model.id();
+        if !(config.is_changed() || instance.is_added()) || modified.contains(&model) {
+            continue;
+        }
+        modified.push(model);
+        let palette: Vec<u8> = config.zones.palette.keys().copied().collect();
+        let modifier = VoxelModifier::new(
+            instance.clone(),
+            mesh.0.clone(),
+            VoxelRegionMode::All,
+            move |_, voxel, _| {
+                if palette.contains(&voxel.0) {
+                    Voxel::EMPTY
+                } else {
+                    voxel.clone()
+                }
+            },
+        );
+        commands.run_system_cached_with(modify_voxel_model, Some(modifier));
+    }
+}
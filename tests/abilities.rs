@@ -0,0 +1,230 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use common::{Harness, PLAYER_HALF_EXTENT};
+use voxel_sampo::{
+    ability::{Dash, DoubleJump, WallContact, WallJump},
+    config::{Config, ConfigError, DashConfig, DoubleJumpConfig, WallJumpConfig},
+    player::Player,
+};
+
+fn settled_player() -> Harness {
+    let mut harness = Harness::new();
+    harness
+        .spawn_flat_floor()
+        .spawn_player(Vec3::new(0.0, 1.0, 0.0))
+        .settle(240);
+    harness
+}
+
+fn insert_ability(harness: &mut Harness, ability: impl Bundle) {
+    let player = player_entity(harness);
+    harness.app.world_mut().entity_mut(player).insert(ability);
+}
+
+fn player_entity(harness: &mut Harness) -> Entity {
+    harness
+        .app
+        .world_mut()
+        .query_filtered::<Entity, With<Player>>()
+        .single(harness.app.world())
+        .expect("exactly one player")
+}
+
+fn tap(harness: &mut Harness, key: KeyCode) {
+    harness.press(key).step(1).release(key);
+}
+
+/// Jumps, waits until the player is falling, then taps jump again and returns its vertical speed.
+fn air_jump_speed(harness: &mut Harness) -> f32 {
+    harness
+        .press(KeyCode::Space)
+        .step(30)
+        .release(KeyCode::Space);
+    assert!(harness.player_velocity().y < 0.0);
+    tap(harness, KeyCode::Space);
+    harness.player_velocity().y
+}
+
+#[test]
+fn double_jump_works_once_per_landing() {
+    let mut harness = settled_player();
+    insert_ability(&mut harness, DoubleJump::default());
+
+    assert!(air_jump_speed(&mut harness) > 1.0);
+    harness.step(30);
+    tap(&mut harness, KeyCode::Space);
+    assert!(harness.player_velocity().y < 0.0);
+
+    harness.settle(240);
+    assert!(air_jump_speed(&mut harness) > 1.0);
+}
+
+#[test]
+fn removing_an_ability_disables_it() {
+    let mut harness = settled_player();
+    insert_ability(&mut harness, DoubleJump::default());
+    let player = player_entity(&mut harness);
+    harness
+        .app
+        .world_mut()
+        .entity_mut(player)
+        .remove::<DoubleJump>();
+
+    assert!(air_jump_speed(&mut harness) < 0.0);
+}
+
+#[test]
+fn wall_jump_pushes_away_from_the_wall() {
+    let mut harness = Harness::new();
+    harness.spawn_flat_floor();
+    // Wall face at x = PLAYER_HALF_EXTENT, touching the player's +x side.
+    harness.app.world_mut().spawn((
+        Transform::from_xyz(PLAYER_HALF_EXTENT + 0.5, 3.0, 0.0),
+        RigidBody::Fixed,
+        Collider::cuboid(0.5, 3.0, 5.0),
+    ));
+    harness.spawn_player(Vec3::new(0.0, 1.0, 0.0)).settle(240);
+    insert_ability(&mut harness, WallJump::default());
+
+    harness
+        .press(KeyCode::Space)
+        .step(30)
+        .release(KeyCode::Space);
+    harness.step(1);
+    let wall_contact = harness
+        .app
+        .world_mut()
+        .query::<&WallContact>()
+        .single(harness.app.world())
+        .expect("wall jump requires a wall contact")
+        .clone();
+    assert_eq!(wall_contact.normal, Some(Vec3::NEG_X));
+
+    tap(&mut harness, KeyCode::Space);
+    let velocity = harness.player_velocity();
+    assert!(velocity.y > 1.0, "{velocity}");
+    assert!(velocity.x < -1.0, "{velocity}");
+}
+
+#[test]
+fn dash_bursts_past_walking_speed_and_cools_down() {
+    let mut harness = settled_player();
+    insert_ability(&mut harness, Dash::default());
+    let dash = Dash::default();
+
+    harness.press(KeyCode::KeyW).step(5);
+    tap(&mut harness, KeyCode::ShiftLeft);
+    let velocity = harness.player_velocity();
+    assert!(
+        (Vec2::new(velocity.x, velocity.z).length() - dash.speed).abs() < 0.5,
+        "{velocity}"
+    );
+
+    harness.step(15);
+    let walking = harness.player_velocity();
+    assert!(Vec2::new(walking.x, walking.z).length() < dash.speed / 2.0);
+
+    // Still cooling down: the second press is ignored.
+    tap(&mut harness, KeyCode::ShiftLeft);
+    let velocity = harness.player_velocity();
+    assert!(Vec2::new(velocity.x, velocity.z).length() < dash.speed / 2.0);
+
+    harness.step(30);
+    tap(&mut harness, KeyCode::ShiftLeft);
+    let velocity = harness.player_velocity();
+    assert!(Vec2::new(velocity.x, velocity.z).length() > dash.speed / 2.0);
+}
+
+#[test]
+fn dash_press_without_the_ability_is_dropped() {
+    let mut harness = settled_player();
+    tap(&mut harness, KeyCode::ShiftLeft);
+    harness.press(KeyCode::KeyW).step(5);
+    insert_ability(&mut harness, Dash::default());
+    harness.step(1);
+
+    let velocity = harness.player_velocity();
+    assert!(Vec2::new(velocity.x, velocity.z).length() < 5.0);
+}
+
+#[test]
+fn abilities_in_the_config_are_granted_on_spawn() {
+    let mut config = Config::default();
+    config.player.abilities.double_jump = Some(DoubleJumpConfig::default());
+    config.player.abilities.dash = Some(DashConfig {
+        speed: 20.0,
+        ..default()
+    });
+    let mut harness = Harness::with_config(config);
+    harness
+        .spawn_flat_floor()
+        .spawn_player(Vec3::new(0.0, 1.0, 0.0))
+        .settle(240);
+
+    let player = player_entity(&mut harness);
+    let world = harness.app.world();
+    assert!(world.get::<WallJump>(player).is_none());
+    assert_eq!(world.get::<Dash>(player).expect("dash granted").speed, 20.0);
+    assert!(air_jump_speed(&mut harness) > 1.0);
+}
+
+#[test]
+fn reloading_the_config_grants_and_removes_abilities() {
+    let mut harness = settled_player();
+    insert_ability(&mut harness, Dash::default());
+    harness.step(1);
+
+    let player = player_entity(&mut harness);
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<Config>()
+        .player
+        .abilities
+        .wall_jump = Some(WallJumpConfig::default());
+    harness.step(1);
+    let world = harness.app.world();
+    assert!(world.get::<WallJump>(player).is_some());
+    assert!(world.get::<Dash>(player).is_some());
+
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<Config>()
+        .player
+        .abilities
+        .wall_jump = None;
+    harness.step(1);
+    assert!(harness.app.world().get::<WallJump>(player).is_none());
+}
+
+#[test]
+fn invalid_abilities_are_rejected() {
+    let mut config = Config::default();
+    config.player.abilities.double_jump = Some(DoubleJumpConfig {
+        charges: 0,
+        ..default()
+    });
+    assert!(matches!(
+        config.validate(),
+        Err(ConfigError::Invalid {
+            field: "player.abilities.double_jump.charges",
+            ..
+        })
+    ));
+
+    config.player.abilities.double_jump = None;
+    config.player.abilities.dash = Some(DashConfig {
+        cooldown: -1.0,
+        ..default()
+    });
+    assert!(matches!(
+        config.validate(),
+        Err(ConfigError::Invalid {
+            field: "player.abilities.dash.cooldown",
+            ..
+        })
+    ));
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use bevy::prelude::*;
+use common::Harness;
+use voxel_sampo::{
+    action::{Action, ActionState, ActionSystems, Binding},
+    config::Config,
+};
+
+fn settled_player(config: Config) -> Harness {
+    let mut harness = Harness::with_config(config);
+    harness
+        .spawn_flat_floor()
+        .spawn_player(Vec3::new(0.0, 1.0, 0.0))
+        .settle(240);
+    harness
+}
+
+fn horizontal_speed(harness: &mut Harness) -> f32 {
+    let velocity = harness.player_velocity();
+    Vec2::new(velocity.x, velocity.z).length()
+}
+
+fn action_state(harness: &Harness) -> ActionState {
+    harness.app.world().resource::<ActionState>().clone()
+}
+
+#[test]
+fn every_binding_triggers_its_action() {
+    let mut config = Config::default();
+    config
+        .bindings
+        .move_forward
+        .push(Binding::Key(KeyCode::ArrowUp));
+
+    let mut harness = settled_player(config);
+    harness.press(KeyCode::ArrowUp).step(30);
+
+    assert!(action_state(&harness).pressed(Action::MoveForward));
+    assert!(horizontal_speed(&mut harness) > 1.0);
+}
+
+#[test]
+fn mouse_buttons_can_be_bound() {
+    let mut config = Config::default();
+    config.bindings.jump = vec![Binding::Mouse(MouseButton::Left)];
+
+    let mut harness = settled_player(config);
+    harness.press(KeyCode::Space).step(10);
+    assert!(harness.grounded_state().is_grounded);
+
+    harness
+        .release(KeyCode::Space)
+        .press_mouse(MouseButton::Left)
+        .step(10);
+    assert!(!harness.grounded_state().is_grounded);
+}
+
+#[test]
+fn just_pressed_lasts_one_frame() {
+    let mut harness = settled_player(Config::default());
+
+    harness.press(KeyCode::Space).step(1);
+    let state = action_state(&harness);
+    assert!(state.pressed(Action::Jump) && state.just_pressed(Action::Jump));
+
+    harness.step(1);
+    let state = action_state(&harness);
+    assert!(state.pressed(Action::Jump) && !state.just_pressed(Action::Jump));
+
+    harness.release(KeyCode::Space).step(1);
+    assert!(action_state(&harness).just_released(Action::Jump));
+}
+
+#[test]
+fn systems_can_drive_actions_without_a_device() {
+    let mut harness = settled_player(Config::default());
+    harness.app.add_systems(
+        PreUpdate,
+        (|mut action_state: ResMut<ActionState>| action_state.press(Action::MoveRight))
+            .after(ActionSystems),
+    );
+    harness.step(30);
+
+    assert!(horizontal_speed(&mut harness) > 1.0);
+}
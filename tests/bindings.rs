@@ -0,0 +1,240 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use bevy::{
+    asset::AssetPlugin,
+    input::mouse::{AccumulatedMouseScroll, MouseScrollUnit},
+    prelude::*,
+};
+use voxel_sampo::{
+    action::{Action, ActionPlugin, ActionState, AxisDirection, Binding},
+    config::{Config, ConfigPlugin, InputBindings, UserSettings},
+    menu::{BindingButton, MenuPlugin, MenuState, ResetBindingsButton},
+};
+
+fn settings_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("voxel-sampo-{name}-{}.ron", std::process::id()))
+}
+
+/// Headless app with the settings menu, saving its settings to `settings_path`.
+fn menu_app(settings: UserSettings, settings_path: Option<PathBuf>) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        ConfigPlugin {
+            settings,
+            settings_path,
+            ..default()
+        },
+        ActionPlugin,
+        MenuPlugin,
+    ))
+    .init_asset::<Font>()
+    .init_resource::<ButtonInput<KeyCode>>()
+    .init_resource::<ButtonInput<MouseButton>>();
+    app.finish();
+    app.cleanup();
+    app.update();
+    app
+}
+
+/// Presses `key` for one frame.
+fn tap(app: &mut App, key: KeyCode) {
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(key);
+    app.update();
+    let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    input.release(key);
+    input.clear();
+    app.update();
+}
+
+fn click_binding_button(app: &mut App, action: Action) {
+    let mut buttons = app
+        .world_mut()
+        .query::<(&BindingButton, &mut Interaction)>();
+    for (button, mut interaction) in buttons.iter_mut(app.world_mut()) {
+        if button.0 == action {
+            *interaction = Interaction::Pressed;
+        }
+    }
+    app.update();
+}
+
+fn click_reset_button(app: &mut App) {
+    let mut buttons = app
+        .world_mut()
+        .query_filtered::<&mut Interaction, With<ResetBindingsButton>>();
+    for mut interaction in buttons.iter_mut(app.world_mut()) {
+        *interaction = Interaction::Pressed;
+    }
+    app.update();
+}
+
+fn bindings(app: &App) -> InputBindings {
+    app.world().resource::<Config>().bindings.clone()
+}
+
+#[test]
+fn rebinding_replaces_only_the_same_device() {
+    let mut bindings = InputBindings::default();
+    let taken_from = bindings.rebind(Action::MoveForward, Binding::Key(KeyCode::KeyZ));
+
+    assert!(taken_from.is_empty());
+    assert_eq!(
+        bindings.move_forward,
+        vec![
+            Binding::GamepadAxis(GamepadAxis::LeftStickY, AxisDirection::Positive),
+            Binding::Key(KeyCode::KeyZ),
+        ]
+    );
+}
+
+#[test]
+fn rebinding_takes_the_binding_from_other_actions() {
+    let mut bindings = InputBindings::default();
+    let taken_from = bindings.rebind(Action::Jump, Binding::Key(KeyCode::KeyW));
+
+    assert_eq!(taken_from, vec![Action::MoveForward]);
+    assert!(!bindings.move_forward.contains(&Binding::Key(KeyCode::KeyW)));
+    assert!(bindings.conflicting_actions().is_empty());
+}
+
+#[test]
+fn shared_bindings_are_reported_as_conflicts() {
+    let mut bindings = InputBindings::default();
+    assert!(bindings.conflicting_actions().is_empty());
+
+    bindings.jump.push(Binding::Key(KeyCode::KeyE));
+    assert_eq!(
+        bindings.conflicting_actions(),
+        vec![Action::Jump, Action::RotateCameraClockwise]
+    );
+}
+
+#[test]
+fn settings_round_trip_through_file() {
+    let settings = UserSettings {
+        bindings: Some(BTreeMap::from([(
+            Action::MoveForward,
+            vec![Binding::Key(KeyCode::KeyZ)],
+        )])),
+    };
+    let path = settings_path("settings-round-trip");
+
+    settings.save(&path).expect("settings save");
+    let loaded = UserSettings::load(&path).expect("settings load");
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(loaded, settings);
+}
+
+#[test]
+fn saved_bindings_survive_a_config_reload() {
+    let mut app = menu_app(
+        UserSettings {
+            bindings: Some(BTreeMap::from([(
+                Action::Jump,
+                vec![Binding::Key(KeyCode::KeyJ)],
+            )])),
+        },
+        None,
+    );
+    assert_eq!(bindings(&app).jump, vec![Binding::Key(KeyCode::KeyJ)]);
+
+    // What the config watcher does when `config.ron` changes.
+    let mut config = Config::default();
+    config.bindings.dash = vec![Binding::Key(KeyCode::KeyX)];
+    app.insert_resource(config);
+    app.update();
+    assert_eq!(bindings(&app).jump, vec![Binding::Key(KeyCode::KeyJ)]);
+    assert_eq!(bindings(&app).dash, vec![Binding::Key(KeyCode::KeyX)]);
+}
+
+#[test]
+fn menu_rebinds_a_key_and_saves_it() {
+    let path = settings_path("settings-menu");
+    let mut app = menu_app(UserSettings::default(), Some(path.clone()));
+
+    tap(&mut app, KeyCode::Escape);
+    assert!(app.world().resource::<MenuState>().open);
+
+    click_binding_button(&mut app, Action::Jump);
+    assert_eq!(
+        app.world().resource::<MenuState>().capturing,
+        Some(Action::Jump)
+    );
+
+    tap(&mut app, KeyCode::KeyJ);
+    let saved = UserSettings::load(&path).expect("settings were saved");
+    std::fs::remove_file(&path).ok();
+
+    let jump = bindings(&app).jump;
+    assert!(jump.contains(&Binding::Key(KeyCode::KeyJ)));
+    assert!(!jump.contains(&Binding::Key(KeyCode::Space)));
+    assert_eq!(
+        saved.bindings,
+        Some(BTreeMap::from([(Action::Jump, jump)])),
+        "only the rebound action is saved"
+    );
+    assert!(app.world().resource::<MenuState>().capturing.is_none());
+}
+
+#[test]
+fn reset_restores_the_config_file_bindings() {
+    let path = settings_path("settings-reset");
+    let mut app = menu_app(UserSettings::default(), Some(path.clone()));
+
+    // What the config watcher does when `config.ron` changes.
+    let mut config = Config::default();
+    config.bindings.jump = vec![Binding::Key(KeyCode::KeyK)];
+    app.insert_resource(config.clone());
+    app.update();
+
+    tap(&mut app, KeyCode::Escape);
+    click_binding_button(&mut app, Action::Jump);
+    tap(&mut app, KeyCode::KeyJ);
+    assert_eq!(bindings(&app).jump, vec![Binding::Key(KeyCode::KeyJ)]);
+
+    click_reset_button(&mut app);
+    let saved = UserSettings::load(&path).expect("settings were saved");
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(bindings(&app), config.bindings);
+    assert_eq!(saved.bindings, None);
+}
+
+#[test]
+fn escape_cancels_capture_and_open_menu_blocks_gameplay() {
+    let mut app = menu_app(UserSettings::default(), None);
+
+    tap(&mut app, KeyCode::Escape);
+    click_binding_button(&mut app, Action::Jump);
+    tap(&mut app, KeyCode::Escape);
+
+    let menu_state = app.world().resource::<MenuState>();
+    assert!(menu_state.open && menu_state.capturing.is_none());
+    assert_eq!(bindings(&app), InputBindings::default());
+
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::Space);
+    app.update();
+    assert!(!app.world().resource::<ActionState>().pressed(Action::Jump));
+}
+
+#[test]
+fn open_menu_blocks_scroll_zoom() {
+    let mut app = menu_app(UserSettings::default(), None);
+    app.insert_resource(AccumulatedMouseScroll {
+        unit: MouseScrollUnit::Line,
+        delta: Vec2::Y,
+    });
+    app.update();
+    assert!(app.world().resource::<ActionState>().zoom() < 0.0);
+
+    tap(&mut app, KeyCode::Escape);
+    assert!(app.world().resource::<MenuState>().open);
+    assert_eq!(app.world().resource::<ActionState>().zoom(), 0.0);
+}
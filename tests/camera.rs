@@ -0,0 +1,588 @@
+mod common;
+
+use bevy::{
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll, MouseScrollUnit},
+    prelude::*,
+    window::{PrimaryWindow, WindowResolution},
+};
+use common::{Harness, VOXEL_SIZE};
+use voxel_sampo::{
+    camera::{
+        CameraAngle, CameraFollow, CameraMode, CameraModeController, CameraPositionController,
+        CameraRotationController, CameraStop, CameraZoomController, LevelBounds,
+    },
+    config::{watcher::watch_config_file, CameraConfig, Config, ConfigWatcher},
+    voxel::VoxelGrid,
+    world::components::{Room, DEFAULT_LEVEL_PATH},
+};
+
+fn stops(yaws: &[f32]) -> Vec<CameraStop> {
+    yaws.iter().copied().map(CameraStop::new).collect()
+}
+
+fn camera_angle(harness: &mut Harness) -> CameraAngle {
+    let mut query = harness
+        .app
+        .world_mut()
+        .query_filtered::<&CameraAngle, With<Camera3d>>();
+    query.single(harness.app.world()).unwrap().clone()
+}
+
+fn camera_mode(harness: &mut Harness) -> CameraMode {
+    let mut query = harness.app.world_mut().query::<&CameraModeController>();
+    query.single(harness.app.world()).unwrap().mode
+}
+
+/// Vertical field of view in degrees, `None` while orthographic.
+fn camera_fov(harness: &mut Harness) -> Option<f32> {
+    let mut query = harness
+        .app
+        .world_mut()
+        .query_filtered::<&Projection, With<Camera3d>>();
+    match query.single(harness.app.world()).unwrap() {
+        Projection::Perspective(perspective) => Some(perspective.fov.to_degrees()),
+        _ => None,
+    }
+}
+
+fn snap_index(harness: &mut Harness) -> usize {
+    let mut query = harness.app.world_mut().query::<&CameraPositionController>();
+    query
+        .single(harness.app.world())
+        .unwrap()
+        .current_snap_index
+}
+
+/// Holds `key` until the camera has come to rest at a different stop.
+fn rotate_one_stop(harness: &mut Harness, key: KeyCode) {
+    let start = snap_index(harness);
+    harness.press(key);
+    for _ in 0..120 {
+        harness.step(1);
+        if snap_index(harness) != start {
+            let mut query = harness.app.world_mut().query::<&CameraRotationController>();
+            let controller = query.single(harness.app.world()).unwrap();
+            assert_eq!(controller.get_current_velocity(), 0.0);
+            harness.release(key);
+            return;
+        }
+    }
+    panic!("camera didn't reach the next stop");
+}
+
+#[test]
+fn default_camera_angle_comes_from_the_config() {
+    let config = CameraConfig::default();
+    let angle = CameraAngle::default();
+    assert_eq!(angle.current_angle, config.start_angle);
+
+    // Stops without a pitch keep the configured height.
+    let transform = angle.get_transform_from_angle(Vec3::ZERO, &config);
+    assert!((transform.translation.y - config.height).abs() < 1e-3);
+
+    let config = CameraConfig {
+        start_angle: 120.0,
+        ..default()
+    };
+    assert_eq!(
+        CameraAngle::new(&config, DEFAULT_LEVEL_PATH).current_angle,
+        150.0
+    );
+}
+
+/// Camera position relative to the point it looks at.
+fn camera_offset(harness: &mut Harness) -> Vec3 {
+    let mut query = harness
+        .app
+        .world_mut()
+        .query::<(&Transform, &CameraFollow)>();
+    let (transform, follow) = query.single(harness.app.world()).unwrap();
+    transform.translation - follow.focus.unwrap()
+}
+
+#[test]
+fn reloading_the_config_moves_the_running_camera() {
+    let path = std::env::temp_dir().join(format!(
+        "voxel-sampo-camera-reload-{}.ron",
+        std::process::id()
+    ));
+    std::fs::write(&path, "(camera: (distance: 40.0, height: 24.0))").unwrap();
+    let mut harness = Harness::with_config(Config::load(&path).unwrap());
+    harness
+        .app
+        .insert_resource(ConfigWatcher::new(&path))
+        .add_systems(Update, watch_config_file);
+    harness
+        .spawn_flat_floor()
+        .spawn_player(Vec3::Y * 0.3)
+        .spawn_camera(DEFAULT_LEVEL_PATH)
+        .settle(300);
+    let offset = camera_offset(&mut harness);
+    assert!((offset.xz().length() - 40.0).abs() < 1e-3, "{offset}");
+    assert!((offset.y - 24.0).abs() < 1e-3, "{offset}");
+
+    std::fs::write(&path, "(camera: (distance: 30.0, height: 30.0))").unwrap();
+    harness.step(60);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(harness.app.world().resource::<Config>().camera.height, 30.0);
+    let offset = camera_offset(&mut harness);
+    assert!((offset.xz().length() - 30.0).abs() < 1e-3, "{offset}");
+    assert!((offset.y - 30.0).abs() < 1e-3, "{offset}");
+}
+
+#[test]
+fn eight_way_rotation_steps_through_every_stop() {
+    let mut config = Config::default();
+    config.camera.snap_angles = stops(&[0.0, 45.0, 90.0, 135.0, 180.0, 225.0, 270.0, 315.0]);
+    config.camera.start_angle = 0.0;
+    let mut harness = Harness::with_config(config);
+    harness.spawn_camera(DEFAULT_LEVEL_PATH).step(1);
+
+    rotate_one_stop(&mut harness, KeyCode::KeyE);
+    assert_eq!(camera_angle(&mut harness).current_angle, 45.0);
+    assert_eq!(snap_index(&mut harness), 1);
+
+    harness.step(10);
+    rotate_one_stop(&mut harness, KeyCode::KeyQ);
+    rotate_one_stop(&mut harness, KeyCode::KeyQ);
+    assert_eq!(camera_angle(&mut harness).current_angle, 315.0);
+    assert_eq!(snap_index(&mut harness), 7);
+}
+
+#[test]
+fn the_view_rotates_smoothly_between_physics_ticks() {
+    // Four rendered frames per physics tick.
+    let mut harness = Harness::with_frame_rate(Config::default(), 240.0);
+    harness.spawn_camera(DEFAULT_LEVEL_PATH).step(1);
+    let mut query = harness
+        .app
+        .world_mut()
+        .query_filtered::<&Transform, With<Camera3d>>();
+
+    harness.press(KeyCode::KeyE).step(8);
+    let mut last = *query.single(harness.app.world()).unwrap();
+    for _ in 0..8 {
+        harness.step(1);
+        let transform = *query.single(harness.app.world()).unwrap();
+        assert!(
+            transform.translation.distance(last.translation) > 1e-4,
+            "the view stood still for a frame"
+        );
+        last = transform;
+    }
+}
+
+#[test]
+fn levels_override_the_snap_angles_and_pitch() {
+    let mut config = Config::default();
+    // Slow enough to stop at every snap angle instead of carrying past it.
+    config.camera.rotation_max_velocity = 200.0;
+    config.camera.level_snap_angles.insert(
+        "tower.vox".to_string(),
+        vec![
+            CameraStop::new(10.0),
+            CameraStop {
+                yaw: 100.0,
+                pitch: Some(60.0),
+            },
+        ],
+    );
+    let mut harness = Harness::with_config(config);
+    harness.spawn_camera("tower.vox").step(1);
+    let default_pitch = CameraConfig::default().default_pitch();
+
+    // 330 is closest to the 10 degree stop.
+    let angle = camera_angle(&mut harness);
+    assert_eq!(angle.current_angle, 10.0);
+    assert_eq!(angle.pitch, default_pitch);
+
+    harness.press(KeyCode::KeyE);
+    let mut pitches = Vec::new();
+    while snap_index(&mut harness) == 0 {
+        harness.step(1);
+        pitches.push(camera_angle(&mut harness).pitch);
+    }
+    harness.release(KeyCode::KeyE);
+    let angle = camera_angle(&mut harness);
+    assert_eq!(angle.current_angle, 100.0);
+    assert_eq!(angle.pitch, 60.0);
+    // The pitch eases over rather than jumping at the end.
+    assert!(pitches
+        .iter()
+        .any(|pitch| *pitch > default_pitch + 1.0 && *pitch < 59.0));
+    assert!(pitches.windows(2).all(|pair| pair[0] <= pair[1]));
+}
+
+#[test]
+fn level_snap_angles_match_levels_opened_from_disk() {
+    let mut config = Config::default();
+    config
+        .camera
+        .level_snap_angles
+        .insert("levels/tower.vox".to_string(), stops(&[10.0, 100.0]));
+    let mut harness = Harness::with_config(config);
+    // `--level levels/tower.vox` loads the scene from the `level://` asset source.
+    harness.spawn_camera("level://tower.vox").step(1);
+    assert_eq!(camera_angle(&mut harness).current_angle, 10.0);
+}
+
+#[test]
+fn without_snap_angles_the_camera_rotates_freely() {
+    let mut config = Config::default();
+    config.camera.snap_angles.clear();
+    let mut harness = Harness::with_config(config);
+    harness.spawn_camera(DEFAULT_LEVEL_PATH).step(1);
+
+    harness.press(KeyCode::KeyE).step(10).release(KeyCode::KeyE);
+    harness.step(30);
+    let resting = camera_angle(&mut harness).current_angle;
+    assert!(resting > 330.0, "stopped at {resting}");
+    harness.step(10);
+    assert_eq!(camera_angle(&mut harness).current_angle, resting);
+}
+
+#[test]
+fn changing_the_snap_angles_moves_the_camera_to_the_nearest_one() {
+    let mut harness = Harness::new();
+    harness.spawn_camera(DEFAULT_LEVEL_PATH).step(1);
+    assert_eq!(camera_angle(&mut harness).current_angle, 330.0);
+
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<Config>()
+        .camera
+        .snap_angles = stops(&[0.0, 180.0]);
+    harness.step(1);
+    assert_eq!(camera_angle(&mut harness).current_angle, 0.0);
+    assert_eq!(snap_index(&mut harness), 0);
+}
+
+#[test]
+fn invalid_snap_angles_are_rejected() {
+    let mut config = Config::default();
+    config.camera.snap_angles = stops(&[90.0, 45.0]);
+    assert!(config.validate().is_err());
+
+    let mut config = Config::default();
+    config.camera.snap_angles = stops(&[0.0, 360.0]);
+    assert!(config.validate().is_err());
+
+    let mut config = Config::default();
+    config.camera.snap_angles[0].pitch = Some(90.0);
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn camera_modes_cycle_with_a_smooth_switch_to_perspective() {
+    let mut harness = Harness::new();
+    harness.spawn_camera(DEFAULT_LEVEL_PATH).step(1);
+    assert_eq!(camera_mode(&mut harness), CameraMode::Snapped);
+    assert_eq!(camera_fov(&mut harness), None);
+
+    harness.press(KeyCode::KeyC).step(1).release(KeyCode::KeyC);
+    assert_eq!(camera_mode(&mut harness), CameraMode::FreeOrbit);
+    harness.step(60);
+    assert_eq!(camera_fov(&mut harness), None);
+
+    // Into third person the field of view widens gradually from nearly orthographic.
+    harness.press(KeyCode::KeyC).step(1).release(KeyCode::KeyC);
+    assert_eq!(camera_mode(&mut harness), CameraMode::ThirdPerson);
+    let mut fovs = Vec::new();
+    for _ in 0..60 {
+        harness.step(1);
+        fovs.push(camera_fov(&mut harness).expect("perspective while blending"));
+    }
+    assert!(fovs[0] < 10.0, "started at {}", fovs[0]);
+    assert!(fovs.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!((fovs.last().unwrap() - 60.0).abs() < 1e-3);
+
+    harness.press(KeyCode::KeyC).step(1).release(KeyCode::KeyC);
+    assert_eq!(camera_mode(&mut harness), CameraMode::Snapped);
+    assert!(camera_fov(&mut harness).is_some());
+    harness.step(60);
+    assert_eq!(camera_fov(&mut harness), None);
+}
+
+#[test]
+fn dragging_orbits_the_camera_only_in_free_modes() {
+    let mut harness = Harness::new();
+    harness.spawn_camera(DEFAULT_LEVEL_PATH).step(1);
+    harness.app.insert_resource(AccumulatedMouseMotion {
+        delta: Vec2::new(40.0, -20.0),
+    });
+    let start = camera_angle(&mut harness);
+
+    // Snapped: dragging does nothing.
+    harness.press_mouse(MouseButton::Right).step(1);
+    harness.release_mouse(MouseButton::Right).step(1);
+    assert_eq!(
+        camera_angle(&mut harness).current_angle,
+        start.current_angle
+    );
+
+    harness.press(KeyCode::KeyC).step(1).release(KeyCode::KeyC);
+    harness.press_mouse(MouseButton::Right).step(1);
+    harness.release_mouse(MouseButton::Right).step(1);
+    let orbited = camera_angle(&mut harness);
+    assert!((orbited.current_angle - (start.current_angle + 10.0)).abs() < 1e-3);
+    assert!((orbited.pitch - (start.pitch - 5.0)).abs() < 1e-3);
+    // Movement follows the orbited camera.
+    assert!(
+        orbited
+            .get_camera_forward_direction()
+            .angle_between(start.get_camera_forward_direction())
+            > 0.1
+    );
+
+    // Pitch stays within the configured range.
+    harness.app.insert_resource(AccumulatedMouseMotion {
+        delta: Vec2::new(0.0, -20.0),
+    });
+    harness.press_mouse(MouseButton::Right).step(30);
+    harness.release_mouse(MouseButton::Right).step(1);
+    let orbit_min_pitch = CameraConfig::default().orbit_min_pitch;
+    assert_eq!(camera_angle(&mut harness).pitch, orbit_min_pitch);
+
+    // Back to snapped, the camera returns to the nearest snap angle.
+    harness.press(KeyCode::KeyC).step(1).release(KeyCode::KeyC);
+    harness.press(KeyCode::KeyC).step(1).release(KeyCode::KeyC);
+    assert_eq!(camera_mode(&mut harness), CameraMode::Snapped);
+    let snapped = camera_angle(&mut harness);
+    assert_eq!(snapped.current_angle, 330.0);
+    assert_eq!(snapped.pitch, start.pitch);
+}
+
+fn zoom_controller(harness: &mut Harness) -> (f32, f32) {
+    let mut query = harness.app.world_mut().query::<&CameraZoomController>();
+    let controller = query.single(harness.app.world()).unwrap();
+    (controller.target_zoom, controller.user_zoom)
+}
+
+#[test]
+fn zoom_keys_zoom_smoothly_within_the_limits() {
+    let mut harness = Harness::new();
+    harness.spawn_camera(DEFAULT_LEVEL_PATH).step(1);
+    let camera = CameraConfig::default();
+
+    harness.press(KeyCode::Minus).step(10);
+    let (target, user) = zoom_controller(&mut harness);
+    assert!(target > camera.base_zoom);
+    assert!(
+        user > camera.base_zoom && user < target,
+        "{user} lags {target}"
+    );
+
+    harness.step(300).release(KeyCode::Minus).step(60);
+    let (target, user) = zoom_controller(&mut harness);
+    assert_eq!(target, camera.max_zoom);
+    assert!((user - camera.max_zoom).abs() < 1e-2);
+
+    harness
+        .press(KeyCode::Equal)
+        .step(300)
+        .release(KeyCode::Equal);
+    assert_eq!(zoom_controller(&mut harness).0, camera.min_zoom);
+}
+
+#[test]
+fn scrolling_up_zooms_in_one_step_per_notch() {
+    let mut harness = Harness::new();
+    harness.spawn_camera(DEFAULT_LEVEL_PATH).step(1);
+    harness.app.insert_resource(AccumulatedMouseScroll {
+        unit: MouseScrollUnit::Line,
+        delta: Vec2::new(0.0, 2.0),
+    });
+    harness.step(1);
+    harness
+        .app
+        .insert_resource(AccumulatedMouseScroll::default());
+    harness.step(60);
+
+    let camera = CameraConfig::default();
+    let expected = camera.base_zoom / camera.zoom_step.powi(2);
+    let (target, user) = zoom_controller(&mut harness);
+    assert!((target - expected).abs() < 1e-4, "{target}");
+    assert!((user - expected).abs() < 1e-2, "{user}");
+}
+
+#[test]
+fn zero_zoom_smoothing_snaps_to_the_chosen_zoom() {
+    let camera = CameraConfig {
+        zoom_smoothing: 0.0,
+        ..default()
+    };
+    let mut controller = CameraZoomController::new(&camera);
+    controller.zoom_by(2.0);
+    controller.update_user_zoom(1.0 / 60.0);
+    assert_eq!(controller.user_zoom, camera.base_zoom * 2.0);
+}
+
+#[test]
+fn rotation_zoom_out_is_relative_to_the_chosen_zoom() {
+    let camera = CameraConfig::default();
+    let mut controller = CameraZoomController::new(&camera);
+    controller.user_zoom = camera.base_zoom / 2.0;
+    for _ in 0..120 {
+        controller.update_zoom_for_velocity(1.0, 1.0, 1.0 / 60.0);
+    }
+    assert!((controller.current_zoom - camera.zoom_out_value / 2.0).abs() < 1e-3);
+}
+
+#[test]
+fn invalid_zoom_limits_are_rejected() {
+    let mut config = Config::default();
+    config.camera.min_zoom = 12.0;
+    assert!(config.validate().is_err());
+
+    let mut config = Config::default();
+    config.camera.zoom_step = 0.5;
+    assert!(config.validate().is_err());
+}
+
+fn follow_at_origin(camera: &CameraConfig) -> CameraFollow {
+    let mut follow = CameraFollow::default();
+    follow.update(
+        Vec3::ZERO,
+        Vec3::ZERO,
+        true,
+        Vec3::X,
+        None,
+        camera,
+        1.0 / 60.0,
+    );
+    follow
+}
+
+#[test]
+fn the_camera_waits_for_the_player_to_leave_the_dead_zone() {
+    let camera = CameraConfig {
+        follow_smooth_time: 0.0,
+        look_ahead_time: 0.0,
+        ..default()
+    };
+    let mut follow = follow_at_origin(&camera);
+    let step = |follow: &mut CameraFollow, player: Vec3| {
+        follow.update(player, Vec3::ZERO, true, Vec3::X, None, &camera, 1.0 / 60.0)
+    };
+
+    // Looking along x, the dead zone is `dead_zone_depth` deep along x and
+    // `dead_zone_width` wide along z.
+    let inside = Vec3::new(camera.dead_zone_depth, 0.0, camera.dead_zone_width) * 0.4;
+    assert_eq!(step(&mut follow, inside), Vec3::ZERO);
+    let focus = step(&mut follow, Vec3::new(0.0, 0.0, 3.0));
+    assert!(focus.abs_diff_eq(
+        Vec3::new(0.0, 0.0, 3.0 - camera.dead_zone_width / 2.0),
+        1e-5
+    ));
+}
+
+#[test]
+fn the_camera_follows_smoothly_and_leads_the_player() {
+    let camera = CameraConfig {
+        dead_zone_width: 0.0,
+        dead_zone_depth: 0.0,
+        ..default()
+    };
+    let mut follow = follow_at_origin(&camera);
+    let player = Vec3::new(4.0, 0.0, 0.0);
+    let mut previous = 0.0;
+    for _ in 0..120 {
+        let focus = follow.update(player, Vec3::ZERO, true, Vec3::X, None, &camera, 1.0 / 60.0);
+        // Critically damped: it closes in without overshooting.
+        assert!(focus.x >= previous && focus.x <= player.x + 1e-4, "{focus}");
+        previous = focus.x;
+    }
+    assert!((previous - player.x).abs() < 1e-2);
+
+    let running = Vec3::new(0.0, 0.0, 100.0);
+    for _ in 0..120 {
+        follow.update(player, running, true, Vec3::X, None, &camera, 1.0 / 60.0);
+    }
+    let lead = follow.focus.unwrap() - player;
+    assert!((lead.z - camera.look_ahead_max).abs() < 1e-2, "{lead}");
+}
+
+#[test]
+fn small_jumps_do_not_move_the_camera_vertically() {
+    let camera = CameraConfig {
+        follow_smooth_time: 0.0,
+        ..default()
+    };
+    let mut follow = follow_at_origin(&camera);
+    let airborne = |follow: &mut CameraFollow, height: f32| {
+        follow.update(
+            Vec3::Y * height,
+            Vec3::ZERO,
+            false,
+            Vec3::X,
+            None,
+            &camera,
+            1.0 / 60.0,
+        )
+    };
+
+    assert_eq!(airborne(&mut follow, 1.0).y, 0.0);
+    let high = camera.vertical_dead_zone + 1.0;
+    assert!((airborne(&mut follow, high).y - 1.0).abs() < 1e-5);
+    // Falling below where it stood, the camera follows the player down.
+    assert_eq!(airborne(&mut follow, -3.0).y, -3.0);
+}
+
+#[test]
+fn the_view_stays_inside_the_level_bounds() {
+    let mut harness = Harness::new();
+    harness.app.world_mut().spawn((
+        Window {
+            resolution: WindowResolution::new(960.0, 720.0),
+            ..default()
+        },
+        PrimaryWindow,
+    ));
+    let grid = VoxelGrid::from_voxels(
+        (-200..200).flat_map(|x| (-200..200).map(move |z| (IVec3::new(x, -1, z), 1))),
+    );
+    harness.spawn_voxel_grid(grid).insert(Room);
+    let half = 200.0 * VOXEL_SIZE;
+    harness
+        .spawn_player(Vec3::new(half - 1.0, 0.3, 1.0 - half))
+        .spawn_camera(DEFAULT_LEVEL_PATH)
+        .step(2);
+
+    let bounds = *harness.app.world().resource::<LevelBounds>();
+    assert!(bounds
+        .min
+        .abs_diff_eq(Vec3::new(-half, -VOXEL_SIZE, -half), 1e-4));
+    assert!(bounds.max.abs_diff_eq(Vec3::new(half, 0.0, half), 1e-4));
+
+    harness.step(120);
+    let mut query = harness
+        .app
+        .world_mut()
+        .query::<(&CameraFollow, &CameraModeController)>();
+    let (follow, mode_controller) = query.single(harness.app.world()).unwrap();
+    let focus = follow.focus.unwrap();
+    // The focus stops half the view short of the level's edges.
+    let view = mode_controller
+        .current_view
+        .unwrap()
+        .ground_half_extents(960.0 / 720.0);
+    assert!(view.x > 1.0 && view.z > 1.0, "{view}");
+    assert!(
+        focus
+            .xz()
+            .abs_diff_eq(Vec2::new(half - view.x, view.z - half), 1e-3),
+        "{focus}"
+    );
+}
+
+#[test]
+fn invalid_follow_settings_are_rejected() {
+    let mut config = Config::default();
+    config.camera.dead_zone_width = -1.0;
+    assert!(config.validate().is_err());
+
+    let mut config = Config::default();
+    config.camera.follow_smooth_time = f32::NAN;
+    assert!(config.validate().is_err());
+}
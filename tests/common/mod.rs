@@ -0,0 +1,314 @@
+//! Headless app harness for driving the player controller in `cargo test`.
+
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use bevy::{asset::AssetPlugin, ecs::system::RunSystemOnce, prelude::*, time::TimeUpdateStrategy};
+use bevy_rapier3d::prelude::*;
+use voxel_sampo::{
+    ability::AbilityPlugin,
+    action::ActionPlugin,
+    camera::{CameraAngle, CameraPlugin},
+    config::{Config, PHYSICS_TICK_RATE},
+    entities::{camera::spawn_camera, player::spawn_player},
+    physics::{PhysicsPlugin, TranslationInterpolation},
+    platform::PlatformPlugin,
+    player::{systems::movement_direction, GroundedState, Player, PlayerPlugin},
+    replay::ReplayPlugin,
+    respawn::RespawnPlugin,
+    voxel::{VoxelCollider, VoxelGrid, VoxelPlugin, Voxels},
+    world::components::Level,
+    zone::ZonePlugin,
+};
+
+pub const VOXEL_SIZE: f32 = 0.05;
+pub const PLAYER_HALF_EXTENT: f32 = 0.25;
+
+/// World direction the player walks while forward is held.
+pub fn forward() -> Vec3 {
+    movement_direction(Vec2::Y, Some(&CameraAngle::default()), true)
+}
+
+pub struct Harness {
+    pub app: App,
+    frame_rate: f64,
+    gamepad: Option<Entity>,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        Self::with_config(Config::default())
+    }
+
+    pub fn with_config(config: Config) -> Self {
+        Self::with_frame_rate(config, PHYSICS_TICK_RATE)
+    }
+
+    /// Renders frames at `frame_rate` while physics ticks at `config.physics.tick_rate`.
+    pub fn with_frame_rate(config: Config, frame_rate: f64) -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            AssetPlugin::default(),
+            RapierPhysicsPlugin::<NoUserData>::default().in_fixed_schedule(),
+            ActionPlugin,
+            CameraPlugin,
+            PlayerPlugin,
+            AbilityPlugin,
+            PhysicsPlugin,
+            PlatformPlugin,
+            ReplayPlugin::default(),
+            VoxelPlugin,
+            ZonePlugin,
+            RespawnPlugin,
+        ))
+        .init_asset::<StandardMaterial>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<ButtonInput<MouseButton>>()
+        .insert_resource(config)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / frame_rate,
+        )));
+
+        // `App::run` is never called, so finish plugin setup by hand.
+        app.finish();
+        app.cleanup();
+
+        // Movement directions are derived from the camera angle.
+        app.world_mut().spawn(CameraAngle::default());
+
+        // Let Rapier create its context before anything physical is spawned.
+        app.update();
+
+        Self {
+            app,
+            frame_rate,
+            gamepad: None,
+        }
+    }
+
+    /// Spawns a large flat floor whose top face is at `y = 0`.
+    pub fn spawn_flat_floor(&mut self) -> &mut Self {
+        self.app.world_mut().spawn((
+            Transform::from_xyz(0.0, -0.5, 0.0),
+            RigidBody::Fixed,
+            Collider::cuboid(50.0, 0.5, 50.0),
+        ));
+        self
+    }
+
+    /// Spawns one fixed box collider per voxel coordinate, `VOXEL_SIZE` units per voxel.
+    pub fn spawn_voxels(&mut self, voxels: impl IntoIterator<Item = IVec3>) -> &mut Self {
+        let half = VOXEL_SIZE / 2.0;
+        for voxel in voxels {
+            let center = voxel.as_vec3() * VOXEL_SIZE + Vec3::splat(half);
+            self.app.world_mut().spawn((
+                Transform::from_translation(center),
+                RigidBody::Fixed,
+                Collider::cuboid(half, half, half),
+            ));
+        }
+        self
+    }
+
+    /// Adds `grid` as an asset and spawns it as fixed level geometry, `VOXEL_SIZE` units per
+    /// voxel, with colliders built from its voxels.
+    pub fn spawn_voxel_grid(&mut self, grid: VoxelGrid) -> EntityWorldMut<'_> {
+        let handle = self
+            .app
+            .world_mut()
+            .resource_mut::<Assets<VoxelGrid>>()
+            .add(grid);
+        self.app.world_mut().spawn((
+            Transform::from_scale(Vec3::splat(VOXEL_SIZE)),
+            RigidBody::Fixed,
+            Voxels(handle),
+            VoxelCollider,
+        ))
+    }
+
+    /// Spawns the player exactly as the game does, at `position`.
+    pub fn spawn_player(&mut self, position: Vec3) -> &mut Self {
+        self.app
+            .world_mut()
+            .run_system_once(
+                move |mut commands: Commands,
+                      mut meshes: ResMut<Assets<Mesh>>,
+                      mut materials: ResMut<Assets<StandardMaterial>>,
+                      config: Res<Config>| {
+                    let level = Level {
+                        spawn_position: position,
+                        ..default()
+                    };
+                    spawn_player(&mut commands, &mut meshes, &mut materials, &config, &level);
+                },
+            )
+            .expect("spawn_player should run");
+        self
+    }
+
+    /// Replaces the bare [`CameraAngle`] with the game's camera rig for the level at `scene_path`.
+    pub fn spawn_camera(&mut self, scene_path: &str) -> &mut Self {
+        let bare: Vec<_> = self
+            .app
+            .world_mut()
+            .query_filtered::<Entity, (With<CameraAngle>, Without<Camera3d>)>()
+            .iter(self.app.world())
+            .collect();
+        for entity in bare {
+            self.app.world_mut().despawn(entity);
+        }
+        let level = Level {
+            scene_path: scene_path.to_string(),
+            ..default()
+        };
+        self.app
+            .world_mut()
+            .run_system_once(move |mut commands: Commands, config: Res<Config>| {
+                spawn_camera(&mut commands, &config, &level);
+            })
+            .expect("spawn_camera should run");
+        self
+    }
+
+    pub fn press(&mut self, key: KeyCode) -> &mut Self {
+        self.input().press(key);
+        self
+    }
+
+    pub fn release(&mut self, key: KeyCode) -> &mut Self {
+        self.input().release(key);
+        self
+    }
+
+    pub fn press_mouse(&mut self, button: MouseButton) -> &mut Self {
+        self.mouse_input().press(button);
+        self
+    }
+
+    pub fn release_mouse(&mut self, button: MouseButton) -> &mut Self {
+        self.mouse_input().release(button);
+        self
+    }
+
+    /// Connects a gamepad with every button released and both sticks centred.
+    pub fn spawn_gamepad(&mut self) -> &mut Self {
+        self.gamepad = Some(self.app.world_mut().spawn(Gamepad::default()).id());
+        self
+    }
+
+    pub fn press_gamepad(&mut self, button: GamepadButton) -> &mut Self {
+        self.gamepad_mut().digital_mut().press(button);
+        self
+    }
+
+    pub fn release_gamepad(&mut self, button: GamepadButton) -> &mut Self {
+        self.gamepad_mut().digital_mut().release(button);
+        self
+    }
+
+    pub fn set_gamepad_axis(&mut self, axis: GamepadAxis, value: f32) -> &mut Self {
+        self.gamepad_mut().analog_mut().set(axis, value);
+        self
+    }
+
+    /// Advances the app by `frames` rendered frames of equal length.
+    pub fn step(&mut self, frames: usize) -> &mut Self {
+        for _ in 0..frames {
+            self.app.update();
+            // Without `InputPlugin` nothing resets the per-frame `just_*` state.
+            self.input().clear();
+            self.mouse_input().clear();
+            if self.gamepad.is_some() {
+                self.gamepad_mut().digital_mut().clear();
+            }
+        }
+        self
+    }
+
+    /// Steps until the player is grounded and has stopped moving, panicking after `max_frames`.
+    pub fn settle(&mut self, max_frames: usize) -> &mut Self {
+        // Frames without a physics tick don't move the body, so require stillness across a tick.
+        let tick_rate = self.app.world().resource::<Config>().physics.tick_rate;
+        let frames_per_tick = (self.frame_rate / tick_rate).ceil() as usize;
+        let mut last_position = self.player_position();
+        let mut still_frames = 0;
+        for _ in 0..max_frames {
+            self.step(1);
+            let position = self.player_position();
+            if self.grounded_state().is_grounded && position.distance(last_position) < 1e-5 {
+                still_frames += 1;
+                if still_frames > frames_per_tick {
+                    return self;
+                }
+            } else {
+                still_frames = 0;
+            }
+            last_position = position;
+        }
+        panic!("player did not settle within {max_frames} frames");
+    }
+
+    /// Steps one frame and returns the player's horizontal speed over it, from displacement.
+    pub fn step_horizontal_speed(&mut self) -> f32 {
+        let start = self.player_position();
+        self.step(1);
+        let delta = self.player_position() - start;
+        Vec2::new(delta.x, delta.z).length() * self.frame_rate as f32
+    }
+
+    /// Simulated position as of the last physics tick, not the interpolated render position.
+    pub fn player_position(&mut self) -> Vec3 {
+        self.player_component::<TranslationInterpolation>().current
+    }
+
+    /// Positions after the last two physics ticks; frames can span more than one tick.
+    pub fn player_interpolation(&mut self) -> TranslationInterpolation {
+        self.player_component::<TranslationInterpolation>()
+    }
+
+    pub fn player_velocity(&mut self) -> Vec3 {
+        self.player_component::<Velocity>().linvel
+    }
+
+    pub fn player_damping(&mut self) -> Damping {
+        self.player_component::<Damping>()
+    }
+
+    pub fn player_gravity_scale(&mut self) -> f32 {
+        self.player_component::<GravityScale>().0
+    }
+
+    pub fn grounded_state(&mut self) -> GroundedState {
+        self.player_component::<GroundedState>()
+    }
+
+    fn player_component<C: Component + Clone>(&mut self) -> C {
+        self.app
+            .world_mut()
+            .query_filtered::<&C, With<Player>>()
+            .single(self.app.world())
+            .expect("exactly one player")
+            .clone()
+    }
+
+    fn input(&mut self) -> Mut<'_, ButtonInput<KeyCode>> {
+        self.app.world_mut().resource_mut::<ButtonInput<KeyCode>>()
+    }
+
+    fn mouse_input(&mut self) -> Mut<'_, ButtonInput<MouseButton>> {
+        self.app
+            .world_mut()
+            .resource_mut::<ButtonInput<MouseButton>>()
+    }
+
+    fn gamepad_mut(&mut self) -> Mut<'_, Gamepad> {
+        let gamepad = self.gamepad.expect("call spawn_gamepad first");
+        self.app
+            .world_mut()
+            .get_mut::<Gamepad>(gamepad)
+            .expect("gamepad entity")
+    }
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use bevy::prelude::*;
+use common::Harness;
+use voxel_sampo::{
+    camera::systems::camera_follow_system,
+    config::{Config, CutawayConfig},
+    cutaway::{systems::update_cutaway_system, Cutaway, CutawaySettings},
+    world::components::DEFAULT_LEVEL_PATH,
+};
+
+/// A player standing on a floor at `y = 0` with the camera looking down along `-to_camera`.
+///
+/// The opacity tests check [`CutawaySettings::opacity_at`], the Rust mirror of `cutaway.wgsl`.
+/// The shader itself never runs here, so changes to it have to be copied to the mirror by hand.
+fn settings(config: &CutawayConfig) -> CutawaySettings {
+    let to_camera = Vec3::new(1.0, 1.0, 0.0).normalize();
+    CutawaySettings::new(config, Vec3::Y * 0.25, 0.0, to_camera)
+}
+
+#[test]
+fn walls_between_the_camera_and_the_player_are_dithered() {
+    let config = CutawayConfig::default();
+    let settings = settings(&config);
+
+    // A wall two metres towards the camera on the line of sight.
+    let wall = settings.player + settings.to_camera * 2.0;
+    assert_eq!(settings.opacity_at(wall), config.occluder_opacity);
+    // Off to the side of the line of sight, past the soft rim.
+    assert_eq!(settings.opacity_at(wall + Vec3::Z * config.radius), 1.0);
+    let rim = settings.opacity_at(wall + Vec3::Z * (config.radius - config.edge / 2.0));
+    assert!(config.occluder_opacity < rim && rim < 1.0, "{rim}");
+    // Behind the player, and the floor in front of it, stay solid.
+    assert_eq!(settings.opacity_at(Vec3::new(-2.0, 0.5, 0.0)), 1.0);
+    assert_eq!(settings.opacity_at(Vec3::new(1.0, 0.0, 0.0)), 1.0);
+}
+
+#[test]
+fn everything_above_the_roof_clearance_is_cut() {
+    let config = CutawayConfig::default();
+    let settings = settings(&config);
+    let clearance = config.roof_clearance.unwrap();
+
+    assert_eq!(
+        settings.opacity_at(Vec3::new(-5.0, clearance + 0.1, 3.0)),
+        0.0
+    );
+    assert_eq!(
+        settings.opacity_at(Vec3::new(-5.0, clearance - 0.1, 3.0)),
+        1.0
+    );
+
+    let kept = CutawaySettings::new(
+        &CutawayConfig {
+            occlusion: false,
+            roof_clearance: None,
+            ..default()
+        },
+        Vec3::Y * 0.25,
+        0.0,
+        Vec3::Y,
+    );
+    assert_eq!(kept.opacity_at(Vec3::Y * 100.0), 1.0);
+}
+
+#[test]
+fn the_floor_height_stays_put_while_jumping() {
+    let mut harness = Harness::new();
+    harness
+        .app
+        .init_resource::<Cutaway>()
+        .add_systems(Update, update_cutaway_system.after(camera_follow_system));
+    harness
+        .spawn_flat_floor()
+        .spawn_player(Vec3::Y * 0.3)
+        .spawn_camera(DEFAULT_LEVEL_PATH)
+        .settle(300);
+
+    let floor_height = |harness: &mut Harness| {
+        let cutaway = harness.app.world().resource::<Cutaway>();
+        cutaway.floor_height.expect("a floor height")
+    };
+    assert!(floor_height(&mut harness).abs() < 0.02);
+
+    harness.press(KeyCode::Space).step(15);
+    assert!(harness.player_position().y > 0.8);
+    assert!(floor_height(&mut harness).abs() < 0.02);
+    harness.release(KeyCode::Space);
+
+    // The cut follows the camera's line of sight to the player.
+    let mut query = harness
+        .app
+        .world_mut()
+        .query_filtered::<&Transform, With<Camera3d>>();
+    let back = query.single(harness.app.world()).unwrap().back();
+    let settings = harness.app.world().resource::<Cutaway>().settings;
+    assert!(settings.to_camera.abs_diff_eq(*back, 1e-5));
+}
+
+#[test]
+fn invalid_cutaway_settings_are_rejected() {
+    let mut config = Config::default();
+    config.cutaway.edge = config.cutaway.radius + 1.0;
+    assert!(config.validate().is_err());
+
+    let mut config = Config::default();
+    config.cutaway.occluder_opacity = 1.5;
+    assert!(config.validate().is_err());
+
+    let mut config = Config::default();
+    config.cutaway.roof_clearance = Some(0.0);
+    assert!(config.validate().is_err());
+}
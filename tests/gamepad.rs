@@ -0,0 +1,104 @@
+mod common;
+
+use bevy::prelude::*;
+use common::Harness;
+use voxel_sampo::config::{Config, GAMEPAD_STICK_DEAD_ZONE, PLAYER_MAX_SPEED};
+
+fn settled_player_with_gamepad(config: Config) -> Harness {
+    let mut harness = Harness::with_config(config);
+    harness
+        .spawn_flat_floor()
+        .spawn_player(Vec3::new(0.0, 1.0, 0.0))
+        .spawn_gamepad()
+        .settle(240);
+    harness
+}
+
+fn horizontal_velocity(harness: &mut Harness) -> Vec2 {
+    let velocity = harness.player_velocity();
+    Vec2::new(velocity.x, velocity.z)
+}
+
+#[test]
+fn partly_deflected_stick_walks_slower() {
+    let mut harness = settled_player_with_gamepad(Config::default());
+    harness
+        .set_gamepad_axis(GamepadAxis::LeftStickY, 0.6)
+        .step(120);
+
+    let expected =
+        PLAYER_MAX_SPEED * (0.6 - GAMEPAD_STICK_DEAD_ZONE) / (1.0 - GAMEPAD_STICK_DEAD_ZONE);
+    let speed = horizontal_velocity(&mut harness).length();
+    assert!(
+        (speed - expected).abs() < 0.1,
+        "speed {speed}, expected {expected}"
+    );
+}
+
+#[test]
+fn stick_inside_dead_zone_is_ignored() {
+    let mut harness = settled_player_with_gamepad(Config::default());
+    harness
+        .set_gamepad_axis(GamepadAxis::LeftStickX, GAMEPAD_STICK_DEAD_ZONE * 0.5)
+        .step(60);
+
+    assert!(horizontal_velocity(&mut harness).length() < 1e-3);
+}
+
+#[test]
+fn diagonal_stick_snaps_to_one_axis() {
+    let mut harness = settled_player_with_gamepad(Config::default());
+    harness
+        .set_gamepad_axis(GamepadAxis::LeftStickX, 0.9)
+        .set_gamepad_axis(GamepadAxis::LeftStickY, 0.5)
+        .step(60);
+
+    let velocity = horizontal_velocity(&mut harness);
+    assert!(velocity.length() > 1.0);
+    assert!(velocity.x.abs().min(velocity.y.abs()) < 1e-3, "{velocity}");
+}
+
+#[test]
+fn diagonal_stick_moves_freely_without_snapping() {
+    let mut config = Config::default();
+    config.player.snap_to_axis = false;
+
+    let mut harness = settled_player_with_gamepad(config);
+    harness
+        .set_gamepad_axis(GamepadAxis::LeftStickX, 0.9)
+        .set_gamepad_axis(GamepadAxis::LeftStickY, 0.5)
+        .step(60);
+
+    let velocity = horizontal_velocity(&mut harness);
+    assert!(velocity.x.abs().min(velocity.y.abs()) > 0.1, "{velocity}");
+}
+
+#[test]
+fn dead_zone_is_radial() {
+    let mut config = Config::default();
+    config.player.snap_to_axis = false;
+
+    // Each axis is inside the dead zone, but the stick as a whole is past it.
+    let axis = GAMEPAD_STICK_DEAD_ZONE * 0.9;
+    let mut harness = settled_player_with_gamepad(config);
+    harness
+        .set_gamepad_axis(GamepadAxis::LeftStickX, axis)
+        .set_gamepad_axis(GamepadAxis::LeftStickY, axis)
+        .step(120);
+
+    let velocity = horizontal_velocity(&mut harness);
+    assert!(velocity.length() > 0.1, "{velocity}");
+}
+
+#[test]
+fn face_button_jumps() {
+    let mut harness = settled_player_with_gamepad(Config::default());
+    harness
+        .press_gamepad(GamepadButton::South)
+        .step(1)
+        .release_gamepad(GamepadButton::South)
+        .step(10);
+
+    assert!(!harness.grounded_state().is_grounded);
+    assert!(harness.player_velocity().y > 0.0);
+}
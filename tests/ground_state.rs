@@ -0,0 +1,92 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use common::{forward, Harness};
+use voxel_sampo::voxel::VoxelGrid;
+
+fn settled_on_floor() -> (Harness, Entity) {
+    let mut harness = Harness::new();
+    let floor = harness
+        .app
+        .world_mut()
+        .spawn((
+            Transform::from_xyz(0.0, -0.5, 0.0),
+            RigidBody::Fixed,
+            Collider::cuboid(50.0, 0.5, 50.0),
+        ))
+        .id();
+    harness.spawn_player(Vec3::new(0.0, 0.5, 0.0)).settle(300);
+    (harness, floor)
+}
+
+#[test]
+fn standing_reports_the_floor_and_time_on_it() {
+    let (mut harness, floor) = settled_on_floor();
+    harness.step(30);
+
+    let state = harness.grounded_state();
+    assert!(state.is_grounded);
+    assert_eq!(state.ground_entity, Some(floor));
+    let distance = state.ground_distance.expect("floor below");
+    assert!(distance.abs() < 0.01, "distance {distance}");
+    assert!(
+        state.grounded_time >= 0.5,
+        "grounded {}",
+        state.grounded_time
+    );
+    assert_eq!(state.airborne_time, 0.0);
+}
+
+#[test]
+fn jumping_tracks_height_and_time_in_the_air() {
+    let (mut harness, floor) = settled_on_floor();
+    harness.press(KeyCode::Space).step(20);
+
+    let state = harness.grounded_state();
+    assert!(!state.is_grounded);
+    assert_eq!(state.ground_entity, None);
+    assert_eq!(state.grounded_time, 0.0);
+    // Leaving the ground takes a tick or two, after which every tick counts.
+    assert!(
+        (0.25..=20.0 / 60.0).contains(&state.airborne_time),
+        "airborne {}",
+        state.airborne_time
+    );
+    let distance = state.ground_distance.expect("floor below");
+    // Ground rays are cast before the physics step, from the previous tick's position.
+    let height = harness.player_interpolation().previous.y - common::PLAYER_HALF_EXTENT;
+    assert!((distance - height).abs() < 0.01, "{distance} vs {height}");
+
+    harness.release(KeyCode::Space).settle(300);
+    assert_eq!(harness.grounded_state().ground_entity, Some(floor));
+}
+
+#[test]
+fn palette_index_under_the_player_is_reported() {
+    let mut harness = Harness::new();
+    // One voxel thick floor 40 voxels wide, index 5 behind the origin and 9 ahead of it.
+    let grid = VoxelGrid::from_voxels((-20..20).flat_map(|x| {
+        (-20..20).map(move |z| {
+            let cell = IVec3::new(x, -1, z);
+            let ahead = (cell.as_vec3() + 0.5).dot(forward()) > 0.0;
+            (cell, if ahead { 9 } else { 5 })
+        })
+    }));
+    harness.spawn_voxel_grid(grid);
+
+    harness
+        .spawn_player(-forward() * 0.5 + Vec3::Y * 0.5)
+        .settle(300);
+    assert_eq!(harness.grounded_state().ground_material, Some(5));
+
+    harness.press(KeyCode::KeyW);
+    for _ in 0..120 {
+        if harness.player_position().dot(forward()) > 0.5 {
+            break;
+        }
+        harness.step(1);
+    }
+    harness.release(KeyCode::KeyW).settle(300);
+    assert_eq!(harness.grounded_state().ground_material, Some(9));
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use common::Harness;
+use voxel_sampo::config::Config;
+
+const DROP_HEIGHT: f32 = 2.0;
+
+fn config(coyote_time: f32, jump_buffer_time: f32) -> Config {
+    let mut config = Config::default();
+    config.player.coyote_time = coyote_time;
+    config.player.jump_buffer_time = jump_buffer_time;
+    config
+}
+
+fn tap_jump(harness: &mut Harness) {
+    harness
+        .press(KeyCode::Space)
+        .step(1)
+        .release(KeyCode::Space);
+}
+
+/// Steps up to `frames` frames and reports whether the player started moving upwards.
+fn rises_within(harness: &mut Harness, frames: usize) -> bool {
+    (0..frames).any(|_| {
+        harness.step(1);
+        harness.player_velocity().y > 0.5
+    })
+}
+
+fn dropped_player(config: Config) -> Harness {
+    let mut harness = Harness::with_config(config);
+    harness
+        .spawn_flat_floor()
+        .spawn_player(Vec3::new(0.0, DROP_HEIGHT, 0.0));
+    harness
+}
+
+/// Frames from the drop until the player first reports being grounded.
+fn frames_until_landing() -> usize {
+    let mut harness = dropped_player(Config::default());
+    (1..600)
+        .find(|_| {
+            harness.step(1);
+            harness.grounded_state().is_grounded
+        })
+        .expect("player lands")
+}
+
+/// Drops the player, taps jump `frames_early` frames before it lands and reports whether it jumped.
+fn jumps_after_early_press(jump_buffer_time: f32, frames_early: usize) -> bool {
+    let landing = frames_until_landing();
+    let mut harness = dropped_player(config(0.0, jump_buffer_time));
+    harness.step(landing - frames_early - 1);
+    assert!(!harness.grounded_state().is_grounded);
+
+    tap_jump(&mut harness);
+    rises_within(&mut harness, frames_early + 10)
+}
+
+/// Removes the floor under a resting player, taps jump `frames_late` frames later and reports
+/// whether it jumped.
+fn jumps_after_late_press(coyote_time: f32, frames_late: usize) -> bool {
+    let mut harness = Harness::with_config(config(coyote_time, 0.0));
+    let floor = harness
+        .app
+        .world_mut()
+        .spawn((
+            Transform::from_xyz(0.0, -0.5, 0.0),
+            RigidBody::Fixed,
+            Collider::cuboid(50.0, 0.5, 50.0),
+        ))
+        .id();
+    harness.spawn_player(Vec3::new(0.0, 1.0, 0.0)).settle(240);
+
+    harness.app.world_mut().despawn(floor);
+    harness.step(frames_late);
+    assert!(!harness.grounded_state().is_grounded);
+
+    tap_jump(&mut harness);
+    rises_within(&mut harness, 10)
+}
+
+#[test]
+fn jump_pressed_just_before_landing_is_buffered() {
+    assert!(jumps_after_early_press(0.1, 3));
+}
+
+#[test]
+fn buffered_jump_expires() {
+    assert!(!jumps_after_early_press(0.1, 12));
+    assert!(!jumps_after_early_press(0.0, 3));
+}
+
+#[test]
+fn jump_pressed_just_after_leaving_the_ground_is_allowed() {
+    assert!(jumps_after_late_press(0.1, 3));
+}
+
+#[test]
+fn coyote_time_expires() {
+    assert!(!jumps_after_late_press(0.1, 12));
+    assert!(!jumps_after_late_press(0.0, 3));
+}
+
+#[test]
+fn jump_cannot_be_repeated_on_the_way_up() {
+    let mut harness = Harness::new();
+    harness
+        .spawn_flat_floor()
+        .spawn_player(Vec3::new(0.0, 1.0, 0.0))
+        .settle(240);
+
+    tap_jump(&mut harness);
+    let launch_speed = harness.player_velocity().y;
+    tap_jump(&mut harness);
+
+    assert!(launch_speed > 0.5);
+    assert!(harness.player_velocity().y < launch_speed);
+}
@@ -0,0 +1,232 @@
+mod common;
+
+use bevy::{asset::AssetPlugin, prelude::*};
+use bevy_rapier3d::prelude::*;
+use common::{Harness, PLAYER_HALF_EXTENT};
+use voxel_sampo::{
+    config::{Config, ConfigPlugin},
+    platform::{Platform, PlatformMotion, PlatformSpec},
+    voxel::VoxelGrid,
+    world::WorldPlugin,
+};
+
+const PLATFORM_HALF_HEIGHT: f32 = 0.1;
+
+/// Spawns a platform with its top face at `y = 0` and the player resting on it at `offset`.
+fn harness_on_platform(motion: PlatformMotion, offset: Vec3) -> (Harness, Entity) {
+    let mut harness = Harness::new();
+    let platform = harness
+        .app
+        .world_mut()
+        .spawn((
+            Transform::from_xyz(0.0, -PLATFORM_HALF_HEIGHT, 0.0),
+            Platform::new(motion),
+            RigidBody::KinematicVelocityBased,
+            Velocity::default(),
+            ReadMassProperties::default(),
+            Collider::cuboid(1.5, PLATFORM_HALF_HEIGHT, 1.5),
+        ))
+        .id();
+    harness.spawn_player(offset + Vec3::Y * (PLAYER_HALF_EXTENT + 0.01));
+    (harness, platform)
+}
+
+fn sliding(distance: Vec3, speed: f32) -> PlatformMotion {
+    PlatformMotion::Waypoints {
+        points: vec![Vec3::ZERO, distance],
+        speed,
+        pause: 0.0,
+        looping: false,
+    }
+}
+
+fn platform_position(harness: &mut Harness, platform: Entity) -> Vec3 {
+    harness
+        .app
+        .world()
+        .get::<Transform>(platform)
+        .expect("platform")
+        .translation
+}
+
+#[test]
+fn player_rides_a_sliding_platform() {
+    let (mut harness, platform) = harness_on_platform(sliding(Vec3::X * 10.0, 2.0), Vec3::ZERO);
+    harness.step(10);
+    let start = harness.player_position() - platform_position(&mut harness, platform);
+
+    harness.step(60);
+    let platform_now = platform_position(&mut harness, platform);
+    assert!(platform_now.x > 1.5, "platform at {platform_now}");
+    let offset = harness.player_position() - platform_now;
+    assert!(
+        offset.distance(start) < 0.05,
+        "player drifted from {start} to {offset} on the platform"
+    );
+    assert!(harness.grounded_state().is_grounded);
+}
+
+#[test]
+fn elevator_lifts_the_player_and_stops_at_the_top() {
+    let elevator = PlatformMotion::Waypoints {
+        points: vec![Vec3::ZERO, Vec3::Y],
+        speed: 1.0,
+        pause: 10.0,
+        looping: false,
+    };
+    let (mut harness, platform) = harness_on_platform(elevator, Vec3::ZERO);
+    harness.step(30);
+    assert!(harness.grounded_state().is_grounded);
+
+    harness.step(90);
+    let top = platform_position(&mut harness, platform).y + PLATFORM_HALF_HEIGHT;
+    assert!((top - 1.0).abs() < 0.05, "platform top at {top}");
+    let player_bottom = harness.player_position().y - PLAYER_HALF_EXTENT;
+    assert!(
+        (player_bottom - top).abs() < 0.03,
+        "player bottom {player_bottom}, platform top {top}"
+    );
+    assert!(harness.grounded_state().is_grounded);
+}
+
+#[test]
+fn player_turns_with_a_spinning_platform() {
+    let spin = PlatformMotion::Rotate {
+        axis: Vec3::Y,
+        speed: 90.0,
+    };
+    let (mut harness, _) = harness_on_platform(spin, Vec3::X * 0.8);
+    harness.step(5);
+    let start = harness.player_position();
+
+    harness.step(60);
+    let end = harness.player_position();
+    let radius = |position: Vec3| Vec2::new(position.x, position.z).length();
+    assert!(
+        (radius(end) - radius(start)).abs() < 0.05,
+        "radius {} -> {}",
+        radius(start),
+        radius(end)
+    );
+    // A quarter turn counter-clockwise seen from above takes +x to -z.
+    let turned = Vec2::new(start.x, start.z).angle_to(Vec2::new(end.x, end.z));
+    assert!(
+        (turned.abs() - std::f32::consts::FRAC_PI_2).abs() < 0.1,
+        "turned {} degrees",
+        turned.to_degrees()
+    );
+}
+
+#[test]
+fn jumping_off_keeps_the_platform_speed() {
+    let (mut harness, _) = harness_on_platform(sliding(Vec3::X * 50.0, 6.0), Vec3::ZERO);
+    harness.step(30);
+
+    harness
+        .press(KeyCode::Space)
+        .step(1)
+        .release(KeyCode::Space)
+        .step(15);
+    assert!(!harness.grounded_state().is_grounded);
+    let velocity = harness.player_velocity();
+    // Faster than the player could walk, and nearly all of it kept through the air.
+    assert!(velocity.x > 5.8, "velocity {velocity}");
+}
+
+#[test]
+fn platforms_are_read_from_the_config_file() {
+    let path =
+        std::env::temp_dir().join(format!("voxel-sampo-platforms-{}.ron", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"(platforms: [
+            (
+                model: "elevator.vox",
+                position: (2.0, 0.0, 0.0),
+                motion: Waypoints(points: [(0.0, 0.0, 0.0), (0.0, 2.0, 0.0)], speed: 1.0),
+            ),
+            (model: "turntable.vox", position: (0.0, 0.0, 4.0), motion: Rotate(axis: (0.0, 1.0, 0.0), speed: 45.0)),
+        ])"#,
+    )
+    .unwrap();
+    let config = Config::load(&path);
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(
+        config.unwrap().platforms,
+        vec![
+            PlatformSpec {
+                model: "elevator.vox".to_string(),
+                position: Vec3::new(2.0, 0.0, 0.0),
+                motion: PlatformMotion::Waypoints {
+                    points: vec![Vec3::ZERO, Vec3::Y * 2.0],
+                    speed: 1.0,
+                    pause: 0.0,
+                    looping: false,
+                },
+            },
+            PlatformSpec {
+                model: "turntable.vox".to_string(),
+                position: Vec3::new(0.0, 0.0, 4.0),
+                motion: PlatformMotion::Rotate {
+                    axis: Vec3::Y,
+                    speed: 45.0,
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn invalid_platforms_are_rejected() {
+    let platform = |motion| PlatformSpec {
+        model: "elevator.vox".to_string(),
+        position: Vec3::ZERO,
+        motion,
+    };
+    let mut config = Config {
+        platforms: vec![platform(sliding(Vec3::Y, 0.0))],
+        ..default()
+    };
+    assert!(config.validate().is_err());
+    config.platforms = vec![platform(PlatformMotion::Rotate {
+        axis: Vec3::ZERO,
+        speed: 45.0,
+    })];
+    assert!(config.validate().is_err());
+    config.platforms = vec![platform(sliding(Vec3::Y, 1.0))];
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn world_spawns_the_platforms_from_the_config() {
+    let config = Config {
+        platforms: vec![PlatformSpec {
+            model: "elevator.vox".to_string(),
+            position: Vec3::new(2.0, 0.0, 0.0),
+            motion: sliding(Vec3::Y, 1.0),
+        }],
+        ..default()
+    };
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        ConfigPlugin {
+            config,
+            ..default()
+        },
+        WorldPlugin::default(),
+    ))
+    .init_asset::<Scene>()
+    .init_asset::<Image>()
+    .init_asset::<VoxelGrid>();
+    app.update();
+
+    let mut platforms = app.world_mut().query::<(&Platform, &Transform)>();
+    let spawned: Vec<_> = platforms
+        .iter(app.world())
+        .map(|(_, transform)| transform.translation)
+        .collect();
+    assert_eq!(spawned, vec![Vec3::new(2.0, 0.0, 0.0)]);
+}
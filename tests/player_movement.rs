@@ -0,0 +1,216 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{Harness, PLAYER_HALF_EXTENT};
+use voxel_sampo::{
+    config::{Config, PLAYER_AIR_DAMPING, PLAYER_GROUND_DAMPING, PLAYER_MAX_SPEED},
+    player::Player,
+};
+
+fn settled_player() -> Harness {
+    settled_player_at_frame_rate(60.0)
+}
+
+fn settled_player_at_frame_rate(frame_rate: f64) -> Harness {
+    settled_player_with(Config::default(), frame_rate)
+}
+
+fn settled_player_with(config: Config, frame_rate: f64) -> Harness {
+    let mut harness = Harness::with_frame_rate(config, frame_rate);
+    harness
+        .spawn_flat_floor()
+        .spawn_player(Vec3::new(0.0, 1.0, 0.0))
+        .settle(240);
+    harness
+}
+
+/// Jumps, holding the button for `hold_frames` frames, and returns how high above its start
+/// the player rises and after how many frames.
+fn jump_apex_held(harness: &mut Harness, hold_frames: usize) -> (f32, usize) {
+    let start_y = harness.player_position().y;
+    harness.press(KeyCode::Space);
+
+    let mut apex = (start_y, 0);
+    for frame in 1..600 {
+        harness.step(1);
+        if frame == hold_frames {
+            harness.release(KeyCode::Space);
+        }
+        let interpolation = harness.player_interpolation();
+        let height = interpolation.previous.y.max(interpolation.current.y);
+        if height > apex.0 {
+            apex = (height, frame);
+        }
+        if harness.player_velocity().y < 0.0 && harness.grounded_state().is_grounded {
+            break;
+        }
+    }
+    harness.release(KeyCode::Space);
+    (apex.0 - start_y, apex.1)
+}
+
+/// Holds jump until the player lands and returns how high above its start it rises.
+fn jump_apex(harness: &mut Harness) -> f32 {
+    jump_apex_held(harness, usize::MAX).0
+}
+
+#[test]
+fn player_comes_to_rest_on_flat_floor() {
+    let mut harness = settled_player();
+
+    let grounded_state = harness.grounded_state();
+    assert!(grounded_state.is_grounded);
+    assert_eq!(grounded_state.hit_count, grounded_state.rays.len());
+    assert!((harness.player_position().y - PLAYER_HALF_EXTENT).abs() < 0.02);
+}
+
+#[test]
+fn player_is_airborne_while_falling() {
+    let mut harness = Harness::new();
+    harness
+        .spawn_flat_floor()
+        .spawn_player(Vec3::new(0.0, 3.0, 0.0))
+        .step(10);
+
+    assert!(!harness.grounded_state().is_grounded);
+    assert!(harness.player_velocity().y < 0.0);
+}
+
+#[test]
+fn walking_reaches_but_never_exceeds_max_speed() {
+    let mut harness = settled_player();
+    harness.press(KeyCode::KeyW);
+
+    let mut top_speed: f32 = 0.0;
+    for _ in 0..120 {
+        harness.step(1);
+        let velocity = harness.player_velocity();
+        top_speed = top_speed.max(Vec2::new(velocity.x, velocity.z).length());
+    }
+
+    assert!(
+        top_speed <= PLAYER_MAX_SPEED + 1e-3,
+        "top speed {top_speed}"
+    );
+    assert!(
+        top_speed >= PLAYER_MAX_SPEED * 0.95,
+        "top speed {top_speed}"
+    );
+}
+
+#[test]
+fn max_speed_follows_config() {
+    let mut config = Config::default();
+    config.player.max_speed = 2.0;
+
+    let mut harness = Harness::with_config(config);
+    harness
+        .spawn_flat_floor()
+        .spawn_player(Vec3::new(0.0, 1.0, 0.0))
+        .settle(240)
+        .press(KeyCode::KeyD)
+        .step(120);
+
+    let speed = harness.step_horizontal_speed();
+    assert!((speed - 2.0).abs() < 0.05, "speed {speed}");
+}
+
+#[test]
+fn held_jump_reaches_configured_apex() {
+    for (jump_height, time_to_apex) in [(2.5, 0.6), (1.3, 0.4), (0.5, 0.25)] {
+        let mut config = Config::default();
+        config.player.jump_height = jump_height;
+        config.player.time_to_apex = time_to_apex;
+        let mut harness = settled_player_with(config, 60.0);
+
+        let (height, frames) = jump_apex_held(&mut harness, usize::MAX);
+        let seconds = frames as f32 / 60.0;
+
+        // Air damping takes a little off the top; the floor contact adds a little at take-off.
+        assert!(
+            (height - jump_height).abs() < jump_height * 0.02 + 0.02,
+            "jump height {height}, configured {jump_height}"
+        );
+        assert!(
+            (seconds - time_to_apex).abs() <= 2.0 / 60.0,
+            "time to apex {seconds}, configured {time_to_apex}"
+        );
+    }
+}
+
+#[test]
+fn releasing_jump_early_cuts_it_short() {
+    let mut harness = settled_player();
+    let full_height = jump_apex(&mut harness);
+    harness.settle(240);
+
+    let (tapped_height, _) = jump_apex_held(&mut harness, 1);
+    assert!(
+        tapped_height < full_height * 0.5,
+        "tapped {tapped_height}, held {full_height}"
+    );
+
+    let mut config = Config::default();
+    config.player.jump_cut = 1.0;
+    let mut uncut = settled_player_with(config, 60.0);
+    let (uncut_height, _) = jump_apex_held(&mut uncut, 1);
+    assert!((uncut_height - full_height).abs() < 0.01);
+}
+
+#[test]
+fn falling_uses_stronger_gravity() {
+    let mut harness = settled_player();
+    let player = Player::default();
+    let rising_scale = player.jump_gravity() / 9.81;
+
+    harness.press(KeyCode::Space).step(5);
+    assert!(harness.player_velocity().y > 0.0);
+    assert!((harness.player_gravity_scale() - rising_scale).abs() < 1e-4);
+
+    harness.step(25);
+    assert!(harness.player_velocity().y < 0.0);
+    assert!(
+        (harness.player_gravity_scale() - rising_scale * player.fall_gravity_multiplier).abs()
+            < 1e-4
+    );
+}
+
+#[test]
+fn jump_is_ignored_while_airborne() {
+    let mut harness = Harness::new();
+    harness
+        .spawn_flat_floor()
+        .spawn_player(Vec3::new(0.0, 3.0, 0.0))
+        .step(10);
+
+    let falling_speed = harness.player_velocity().y;
+    harness.press(KeyCode::Space).step(1);
+
+    assert!(harness.player_velocity().y <= falling_speed);
+}
+
+#[test]
+fn damping_switches_between_ground_and_air() {
+    let mut harness = settled_player();
+    assert_eq!(
+        harness.player_damping().linear_damping,
+        PLAYER_GROUND_DAMPING
+    );
+
+    harness.press(KeyCode::Space).step(10);
+    assert_eq!(harness.player_damping().linear_damping, PLAYER_AIR_DAMPING);
+}
+
+#[test]
+fn jump_arc_does_not_depend_on_frame_rate() {
+    let mut slow = settled_player_at_frame_rate(30.0);
+    let mut fast = settled_player_at_frame_rate(144.0);
+
+    let slow_height = jump_apex(&mut slow);
+    let fast_height = jump_apex(&mut fast);
+
+    assert!(
+        (slow_height - fast_height).abs() < 1e-4,
+        "30 Hz: {slow_height}, 144 Hz: {fast_height}"
+    );
+}
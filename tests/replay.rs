@@ -0,0 +1,120 @@
+mod common;
+
+use bevy::prelude::*;
+use common::Harness;
+use voxel_sampo::replay::{InputRecorder, InputRecording, InputReplay};
+
+const SPAWN_POSITION: Vec3 = Vec3::new(0.0, 1.0, 0.0);
+
+/// Walks, jumps and turns with the recorder attached, then returns what it captured.
+fn record_session() -> InputRecording {
+    let mut harness = Harness::new();
+    harness.app.insert_resource(InputRecorder::default());
+    harness
+        .spawn_flat_floor()
+        .spawn_player(SPAWN_POSITION)
+        .step(60)
+        .press(KeyCode::KeyW)
+        .step(30)
+        .press(KeyCode::Space)
+        .step(1)
+        .release(KeyCode::Space)
+        .step(20)
+        .release(KeyCode::KeyW)
+        .press(KeyCode::KeyD)
+        .step(40)
+        .release(KeyCode::KeyD)
+        .step(60);
+
+    harness
+        .app
+        .world_mut()
+        .remove_resource::<InputRecorder>()
+        .expect("recorder is still attached")
+        .recording
+}
+
+/// Plays `recording` back on a fresh app until every tick has been consumed.
+fn replay(recording: InputRecording) -> InputReplay {
+    let frames = recording.ticks.len() + 10;
+    let mut harness = Harness::new();
+    harness.app.insert_resource(InputReplay::new(recording));
+    harness
+        .spawn_flat_floor()
+        .spawn_player(SPAWN_POSITION)
+        .step(frames);
+
+    harness
+        .app
+        .world_mut()
+        .remove_resource::<InputReplay>()
+        .expect("replay is still attached")
+}
+
+#[test]
+fn recording_captures_each_tick() {
+    let recording = record_session();
+
+    assert_eq!(recording.spawn_position, SPAWN_POSITION);
+    assert_eq!(recording.ticks.iter().filter(|tick| tick.jump).count(), 1);
+    assert!(recording.ticks.iter().any(|tick| tick.movement == Vec2::Y));
+    assert!(recording.ticks.iter().any(|tick| tick.movement == Vec2::X));
+    assert!(recording.final_position.distance(SPAWN_POSITION) > 1.0);
+}
+
+#[test]
+fn replay_reproduces_recorded_position() {
+    let recording = record_session();
+    let replay = replay(recording);
+
+    let outcome = replay.outcome.expect("replay finished");
+    assert!(outcome.matched, "{outcome:?}");
+}
+
+#[test]
+fn replay_reports_diverging_position() {
+    let mut recording = record_session();
+    recording.final_position += Vec3::X;
+    let replay = replay(recording);
+
+    let outcome = replay.outcome.expect("replay finished");
+    assert!(!outcome.matched, "{outcome:?}");
+}
+
+#[test]
+fn recording_round_trips_through_file() {
+    let recording = record_session();
+    let path = std::env::temp_dir().join(format!("voxel-sampo-replay-{}.ron", std::process::id()));
+
+    recording.save(&path).expect("recording saves");
+    let loaded = InputRecording::load(&path).expect("recording loads");
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(loaded, recording);
+}
+
+#[test]
+fn recording_is_written_out_while_it_runs() {
+    let path = std::env::temp_dir().join(format!(
+        "voxel-sampo-replay-flush-{}.ron",
+        std::process::id()
+    ));
+    let mut harness = Harness::new();
+    harness.app.insert_resource(InputRecorder::new(&path));
+    harness
+        .spawn_flat_floor()
+        .spawn_player(SPAWN_POSITION)
+        .press(KeyCode::KeyW)
+        .step(90);
+
+    // No `AppExit`: a crash at this point keeps everything up to the last flush.
+    let saved = InputRecording::load(&path).expect("recording was written");
+    std::fs::remove_file(&path).ok();
+    let recorder = harness.app.world().resource::<InputRecorder>();
+    assert!(saved.ticks.len() >= 60, "{} ticks", saved.ticks.len());
+    assert_eq!(saved.ticks.len(), recorder.saved_ticks);
+    assert_eq!(
+        saved.ticks[..],
+        recorder.recording.ticks[..saved.ticks.len()]
+    );
+}
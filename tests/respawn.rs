@@ -0,0 +1,185 @@
+mod common;
+
+use bevy::{ecs::event::EventCursor, prelude::*};
+use common::{forward, Harness, VOXEL_SIZE};
+use voxel_sampo::{
+    camera::CameraFollow,
+    config::Config,
+    player::Player,
+    respawn::{
+        Checkpoint, CheckpointActivated, Invulnerable, PlayerRespawned, RespawnCause, RespawnFade,
+    },
+    voxel::{SurfaceMaterial, SurfaceTag, VoxelGrid},
+    world::components::DEFAULT_LEVEL_PATH,
+};
+
+/// Palette index reserved for the `checkpoint-1` zone.
+const CHECKPOINT: u8 = 200;
+/// Palette index reserved for the `kill` zone.
+const KILL: u8 = 201;
+
+fn respawn_config() -> Config {
+    let mut config = Config::default();
+    config.respawn.kill_height = -2.0;
+    config
+        .zones
+        .palette
+        .insert(CHECKPOINT, "checkpoint-1".to_string());
+    config.zones.palette.insert(KILL, "kill".to_string());
+    config
+}
+
+/// A voxel floor with its top at `y = 0` that ends `edge` metres ahead of the origin, with a
+/// wall of `zone` voxels standing on it one metre ahead.
+fn harness_with_zone(zone: u8, edge: f32) -> Harness {
+    let mut harness = Harness::with_config(respawn_config());
+    let distance = |cell: IVec3| (cell.as_vec3() + 0.5).dot(forward()) * VOXEL_SIZE;
+    let grid = VoxelGrid::from_voxels((-80..80).flat_map(|x| {
+        (-80..80).flat_map(move |z| {
+            let floor = Some((IVec3::new(x, -1, z), 1)).filter(|(cell, _)| distance(*cell) < edge);
+            let wall = (0..20)
+                .map(move |y| IVec3::new(x, y, z))
+                .filter(move |cell| (0.9..1.1).contains(&distance(*cell)))
+                .map(move |cell| (cell, zone));
+            floor.into_iter().chain(wall)
+        })
+    }));
+    harness.spawn_voxel_grid(grid);
+    harness
+}
+
+fn player_component<T: Component + Clone>(harness: &mut Harness) -> T {
+    let mut query = harness.app.world_mut().query_filtered::<&T, With<Player>>();
+    query.single(harness.app.world()).unwrap().clone()
+}
+
+/// Steps until an event of type `E` is sent, panicking after `max_frames`.
+fn step_until<E: Event + Clone>(harness: &mut Harness, max_frames: usize) -> E {
+    let mut cursor = EventCursor::<E>::default();
+    for _ in 0..max_frames {
+        harness.step(1);
+        let events = harness.app.world().resource::<Events<E>>();
+        if let Some(event) = cursor.read(events).next() {
+            return event.clone();
+        }
+    }
+    panic!(
+        "no {} within {max_frames} frames",
+        std::any::type_name::<E>()
+    );
+}
+
+#[test]
+fn falling_out_of_the_world_respawns_at_the_spawn_position() {
+    let mut harness = Harness::with_config(respawn_config());
+    let spawn = Vec3::new(1.0, 1.0, -2.0);
+    harness.spawn_player(spawn).spawn_camera(DEFAULT_LEVEL_PATH);
+
+    // Nothing to stand on: the player falls until it crosses the kill height.
+    let respawn = step_until::<PlayerRespawned>(&mut harness, 120);
+    assert_eq!(respawn.cause, RespawnCause::FellOut);
+    assert_eq!(respawn.position, spawn);
+
+    let position = harness.player_position();
+    assert!(position.distance(spawn) < 0.1, "respawned at {position}");
+    assert!(harness.player_velocity().length() < 1.0);
+    assert!(player_component::<Invulnerable>(&mut harness).is_active());
+
+    // The camera jumps to the checkpoint instead of panning back up.
+    let mut cameras = harness.app.world_mut().query::<&CameraFollow>();
+    let focus = cameras.single(harness.app.world()).unwrap().focus.unwrap();
+    assert!(focus.distance(spawn) < 0.1, "camera focus at {focus}");
+
+    let mut query = harness
+        .app
+        .world_mut()
+        .query::<(&RespawnFade, &BackgroundColor)>();
+    let (fade, background) = query.single(harness.app.world()).unwrap();
+    assert!(fade.remaining > 0.0);
+    assert!(background.0.alpha() > 0.5);
+}
+
+#[test]
+fn checkpoint_zones_move_the_respawn_point() {
+    let mut harness = harness_with_zone(CHECKPOINT, 1.5);
+    harness
+        .spawn_player(Vec3::Y * 0.3)
+        .settle(300)
+        .press(KeyCode::KeyW);
+
+    // Walk through the checkpoint and off the end of the floor.
+    let activated = step_until::<CheckpointActivated>(&mut harness, 60);
+    assert_eq!(activated.zone, "checkpoint-1");
+    let respawn = step_until::<PlayerRespawned>(&mut harness, 120);
+    assert_eq!(respawn.cause, RespawnCause::FellOut);
+    assert_eq!(respawn.position, activated.position);
+    let checkpoint = player_component::<Checkpoint>(&mut harness);
+    assert_eq!(checkpoint.zone.as_deref(), Some("checkpoint-1"));
+    assert_eq!(checkpoint.position, activated.position);
+
+    harness.release(KeyCode::KeyW);
+    harness.settle(300);
+    let along = harness.player_position().dot(forward());
+    assert!((0.5..1.5).contains(&along), "respawned {along} ahead");
+}
+
+#[test]
+fn kill_zones_respawn_the_player_unless_it_is_invulnerable() {
+    let mut harness = harness_with_zone(KILL, 10.0);
+    harness
+        .spawn_player(Vec3::Y * 0.3)
+        .settle(300)
+        .press(KeyCode::KeyW);
+
+    let respawn = step_until::<PlayerRespawned>(&mut harness, 60);
+    assert_eq!(respawn.cause, RespawnCause::KillZone("kill".to_string()));
+    assert!(harness.player_position().length() < 0.5);
+
+    // While invulnerable the player walks through the kill zone unharmed.
+    harness.release(KeyCode::KeyW).settle(300);
+    let mut query = harness
+        .app
+        .world_mut()
+        .query_filtered::<&mut Invulnerable, With<Player>>();
+    query.single_mut(harness.app.world_mut()).unwrap().remaining = 100.0;
+    harness.press(KeyCode::KeyW);
+    let mut cursor = EventCursor::<PlayerRespawned>::default();
+    for _ in 0..60 {
+        harness.step(1);
+        let events = harness.app.world().resource::<Events<PlayerRespawned>>();
+        assert_eq!(cursor.read(events).next(), None);
+    }
+    assert!(harness.player_position().dot(forward()) > 1.5);
+}
+
+#[test]
+fn landing_on_lava_respawns_the_player() {
+    let mut config = respawn_config();
+    let lava = SurfaceMaterial {
+        tag: SurfaceTag::Lava,
+        ..default()
+    };
+    config.surfaces.palette.insert(1, lava);
+    let mut harness = Harness::with_config(config);
+    let grid = VoxelGrid::from_voxels(
+        (-20..20).flat_map(|x| (-20..20).map(move |z| (IVec3::new(x, -1, z), 1))),
+    );
+    harness.spawn_voxel_grid(grid);
+
+    let spawn = Vec3::Y * 0.5;
+    harness.spawn_player(spawn);
+    let respawn = step_until::<PlayerRespawned>(&mut harness, 60);
+    assert_eq!(respawn.cause, RespawnCause::Lava);
+    assert!(harness.player_position().distance(spawn) < 0.1);
+}
+
+#[test]
+fn invalid_respawn_settings_are_rejected() {
+    let mut config = Config::default();
+    config.respawn.kill_zones.push(String::new());
+    assert!(config.validate().is_err());
+
+    let mut config = Config::default();
+    config.respawn.fade_time = -1.0;
+    assert!(config.validate().is_err());
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use common::{forward, Harness};
+use dot_vox::{Dict, DotVoxData, Material};
+use voxel_sampo::{
+    config::{Config, SurfaceConfig},
+    voxel::{SurfaceMaterial, SurfaceTag, VoxelColliderPart, VoxelGrid},
+};
+
+const ICE: SurfaceMaterial = SurfaceMaterial {
+    friction: 0.0,
+    restitution: 0.0,
+    tag: SurfaceTag::Ice,
+};
+
+const BOUNCY: SurfaceMaterial = SurfaceMaterial {
+    friction: 0.5,
+    restitution: 0.9,
+    tag: SurfaceTag::Bouncy,
+};
+
+/// Ice keyed by palette index 3 and bouncy by the `metal` material type.
+fn surfaces() -> SurfaceConfig {
+    SurfaceConfig {
+        palette: [(3, ICE)].into(),
+        material_types: [("metal".to_string(), BOUNCY)].into(),
+        ..default()
+    }
+}
+
+/// Spawns a voxel floor one voxel thick with its top at `y = 0`, every voxel using `index`.
+fn spawn_floor(harness: &mut Harness, index: u8) -> Entity {
+    let grid = VoxelGrid::from_voxels(
+        (-60..60).flat_map(|x| (-60..60).map(move |z| (IVec3::new(x, -1, z), index))),
+    );
+    harness.spawn_voxel_grid(grid).id()
+}
+
+fn surface_config() -> Config {
+    Config {
+        surfaces: surfaces(),
+        ..default()
+    }
+}
+
+/// Distance the player slides after walking forward at full speed and letting go.
+fn slide_distance(index: u8) -> f32 {
+    let mut harness = Harness::with_config(surface_config());
+    spawn_floor(&mut harness, index);
+    harness
+        .spawn_player(-forward() * 2.0 + Vec3::Y * 0.3)
+        .settle(300)
+        .press(KeyCode::KeyW)
+        .step(60)
+        .release(KeyCode::KeyW);
+    let released_at = harness.player_position();
+    harness.step(60);
+    (harness.player_position() - released_at).dot(forward())
+}
+
+#[test]
+fn material_types_are_read_from_matl_chunks() {
+    let material = |id, kind: &str| Material {
+        id,
+        properties: Dict::from([("_type".to_string(), kind.to_string())]),
+    };
+    let file = DotVoxData {
+        version: 150,
+        models: Vec::new(),
+        palette: Vec::new(),
+        materials: vec![material(12, "_glass"), material(13, "_diffuse")],
+        scenes: Vec::new(),
+        layers: Vec::new(),
+    };
+
+    let grid = VoxelGrid::from_vox(&file);
+    assert_eq!(grid.material_type(12), Some("glass"));
+    assert_eq!(grid.material_type(13), Some("diffuse"));
+    assert_eq!(grid.material_type(14), None);
+}
+
+#[test]
+fn palette_index_takes_precedence_over_material_type() {
+    let surfaces = surfaces();
+    assert_eq!(surfaces.resolve(3, Some("metal")), ICE);
+    assert_eq!(surfaces.resolve(4, Some("metal")), BOUNCY);
+    assert_eq!(
+        surfaces.resolve(4, Some("glass")),
+        SurfaceMaterial::default()
+    );
+    assert_eq!(surfaces.resolve(4, None), SurfaceMaterial::default());
+}
+
+#[test]
+fn invalid_surfaces_are_rejected() {
+    let mut config = surface_config();
+    config.surfaces.palette.insert(0, ICE);
+    assert!(config.validate().is_err());
+
+    let mut config = surface_config();
+    config.surfaces.default.friction = -1.0;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn each_surface_gets_its_own_collider() {
+    let mut harness = Harness::with_config(surface_config());
+    // Ice in front of the origin and plain floor behind it.
+    let grid =
+        VoxelGrid::from_voxels((-10..10).flat_map(|x| {
+            (-10..10).map(move |z| (IVec3::new(x, -1, z), if z < 0 { 3 } else { 1 }))
+        }));
+    let floor = harness.spawn_voxel_grid(grid).id();
+    harness.step(1);
+
+    let mut query = harness.app.world_mut().query_filtered::<
+        (&ChildOf, &SurfaceMaterial, &Friction, &Restitution),
+        With<VoxelColliderPart>,
+    >();
+    let mut parts: Vec<_> = query
+        .iter(harness.app.world())
+        .map(|(child_of, surface, friction, restitution)| {
+            assert_eq!(child_of.parent(), floor);
+            assert_eq!(friction.coefficient, surface.friction);
+            assert_eq!(restitution.coefficient, surface.restitution);
+            surface.tag
+        })
+        .collect();
+    parts.sort_by_key(|tag| *tag as u8);
+    assert_eq!(parts, vec![SurfaceTag::Solid, SurfaceTag::Ice]);
+
+    // Changing the surfaces rebuilds the parts.
+    harness.app.world_mut().resource_mut::<Config>().surfaces = SurfaceConfig::default();
+    harness.step(1);
+    let count = harness
+        .app
+        .world_mut()
+        .query_filtered::<(), With<VoxelColliderPart>>()
+        .iter(harness.app.world())
+        .count();
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn player_slides_further_on_ice() {
+    let mut harness = Harness::with_config(surface_config());
+    spawn_floor(&mut harness, 3);
+    harness.spawn_player(Vec3::Y * 0.3).settle(300);
+    assert_eq!(
+        harness.grounded_state().ground_surface,
+        Some(SurfaceTag::Ice)
+    );
+
+    let on_floor = slide_distance(1);
+    let on_ice = slide_distance(3);
+    assert!(on_floor < 0.5, "slid {on_floor} on the floor");
+    assert!(on_ice > 2.0 * on_floor.max(0.1), "slid {on_ice} on ice");
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use common::{forward, Harness, PLAYER_HALF_EXTENT};
+
+fn harness_with_floor() -> Harness {
+    let mut harness = Harness::new();
+    harness.spawn_flat_floor();
+    harness
+}
+
+/// Spawns a wide block `height` tall whose near face is `distance` ahead of the origin.
+fn spawn_ledge(harness: &mut Harness, distance: f32, height: f32) {
+    let half_depth = 2.0;
+    harness.app.world_mut().spawn((
+        Transform::from_translation(forward() * (distance + half_depth) + Vec3::Y * height / 2.0),
+        RigidBody::Fixed,
+        Collider::cuboid(half_depth, height / 2.0, half_depth),
+    ));
+}
+
+/// Spawns a ramp rising along `forward()` at `angle` degrees, with its surface through the origin.
+fn spawn_ramp(harness: &mut Harness, angle: f32) {
+    let axis = Vec3::Y.cross(forward());
+    let rotation = Quat::from_axis_angle(axis, -angle.to_radians());
+    harness.app.world_mut().spawn((
+        Transform::from_translation(rotation * Vec3::new(0.0, -0.5, 0.0)).with_rotation(rotation),
+        RigidBody::Fixed,
+        Collider::cuboid(20.0, 0.5, 20.0),
+    ));
+}
+
+fn distance_forward(harness: &mut Harness) -> f32 {
+    harness.player_position().dot(forward())
+}
+
+#[test]
+fn player_steps_up_a_single_voxel_ledge() {
+    let mut harness = harness_with_floor();
+    spawn_ledge(&mut harness, 0.5, 0.05);
+    harness
+        .spawn_player(Vec3::new(0.0, 1.0, 0.0))
+        .settle(240)
+        .press(KeyCode::KeyW)
+        .step(60);
+
+    assert!(distance_forward(&mut harness) > 1.0);
+    assert!(harness.player_position().y > 0.05 + PLAYER_HALF_EXTENT - 0.01);
+}
+
+#[test]
+fn ledge_taller_than_step_height_blocks_the_player() {
+    let mut harness = harness_with_floor();
+    spawn_ledge(&mut harness, 0.5, 0.2);
+    harness
+        .spawn_player(Vec3::new(0.0, 1.0, 0.0))
+        .settle(240)
+        .press(KeyCode::KeyW)
+        .step(60);
+
+    assert!(distance_forward(&mut harness) < 0.5 - PLAYER_HALF_EXTENT + 0.01);
+    assert!(harness.player_position().y < PLAYER_HALF_EXTENT + 0.01);
+}
+
+#[test]
+fn ground_normal_follows_a_walkable_slope() {
+    let mut harness = Harness::new();
+    spawn_ramp(&mut harness, 20.0);
+    harness.spawn_player(Vec3::new(0.0, 1.0, 0.0)).settle(240);
+
+    let grounded_state = harness.grounded_state();
+    assert!(grounded_state.is_grounded && !grounded_state.on_steep_slope);
+    let tilt = grounded_state
+        .ground_normal
+        .angle_between(Vec3::Y)
+        .to_degrees();
+    assert!((tilt - 20.0).abs() < 1.0, "tilt {tilt}");
+
+    let start_height = harness.player_position().y;
+    harness.press(KeyCode::KeyW).step(60);
+    assert!(harness.player_position().y > start_height + 0.3);
+}
+
+#[test]
+fn player_slides_down_a_slope_too_steep_to_stand_on() {
+    let mut harness = Harness::new();
+    spawn_ramp(&mut harness, 60.0);
+    harness.spawn_player(Vec3::new(0.0, 0.6, 0.0)).step(10);
+
+    let grounded_state = harness.grounded_state();
+    assert!(!grounded_state.is_grounded && grounded_state.on_steep_slope);
+
+    let start = distance_forward(&mut harness);
+    harness.press(KeyCode::KeyW).step(30);
+    assert!(distance_forward(&mut harness) < start - 0.2);
+}
@@ -0,0 +1,369 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use common::{forward, Harness};
+use dot_vox::{Dict, DotVoxData, Frame, Layer, Model, SceneNode, ShapeModel, Size, Voxel};
+use voxel_sampo::voxel::VoxelGrid;
+
+fn model(size: [u32; 3], voxels: &[[u8; 4]]) -> Model {
+    Model {
+        size: Size {
+            x: size[0],
+            y: size[1],
+            z: size[2],
+        },
+        voxels: voxels
+            .iter()
+            .map(|&[x, y, z, index]| Voxel {
+                x,
+                y,
+                z,
+                i: index - 1,
+            })
+            .collect(),
+    }
+}
+
+fn transform_node(child: u32, name: Option<&str>, layer_id: u32, translation: &str) -> SceneNode {
+    SceneNode::Transform {
+        attributes: name
+            .map(|name| ("_name".to_string(), name.to_string()))
+            .into_iter()
+            .collect(),
+        frames: vec![Frame::new(Dict::from([(
+            "_t".to_string(),
+            translation.to_string(),
+        )]))],
+        child,
+        layer_id,
+    }
+}
+
+fn shape_node(model_id: u32) -> SceneNode {
+    SceneNode::Shape {
+        attributes: Dict::new(),
+        models: vec![ShapeModel {
+            model_id,
+            attributes: Dict::new(),
+        }],
+    }
+}
+
+fn vox_file(models: Vec<Model>, scenes: Vec<SceneNode>, layers: Vec<Layer>) -> DotVoxData {
+    DotVoxData {
+        version: 150,
+        models,
+        palette: Vec::new(),
+        materials: Vec::new(),
+        scenes,
+        layers,
+    }
+}
+
+/// One 2x2x2 model placed twice: at the origin and, named "door", ten voxels along x.
+fn two_instance_file() -> DotVoxData {
+    vox_file(
+        vec![model([2, 2, 2], &[[1, 0, 1, 7]])],
+        vec![
+            transform_node(1, None, 0, "0 0 0"),
+            SceneNode::Group {
+                attributes: Dict::new(),
+                children: vec![2, 3],
+            },
+            transform_node(4, None, 0, "0 0 0"),
+            transform_node(4, Some("door"), 0, "10 0 0"),
+            shape_node(0),
+        ],
+        Vec::new(),
+    )
+}
+
+/// One 1x1x1 model placed three times: as geometry, named `zone:goal`, and on a layer named
+/// `zone:water`.
+fn zone_file() -> DotVoxData {
+    vox_file(
+        vec![model([1, 1, 1], &[[0, 0, 0, 4]])],
+        vec![
+            transform_node(1, None, 0, "0 0 0"),
+            SceneNode::Group {
+                attributes: Dict::new(),
+                children: vec![2, 3, 4],
+            },
+            transform_node(5, None, 0, "0 0 0"),
+            transform_node(5, Some("zone:goal"), 0, "5 0 0"),
+            transform_node(5, None, 1, "0 0 5"),
+            shape_node(0),
+        ],
+        vec![
+            Layer {
+                attributes: Dict::new(),
+            },
+            Layer {
+                attributes: Dict::from([("_name".to_string(), "zone:water".to_string())]),
+            },
+        ],
+    )
+}
+
+#[test]
+fn grid_converts_magicavoxel_axes_to_y_up() {
+    let grid = VoxelGrid::from_vox(&two_instance_file());
+
+    // Voxel (1, 0, 1) of a model centred on the origin sits in MagicaVoxel cell (0, -1, 0),
+    // which is (-1, 0, -1) with x mirrored and y and z swapped.
+    assert_eq!(grid.get(IVec3::new(-1, 0, -1)), Some(7));
+    // The instance named "door" is ten voxels along MagicaVoxel's x.
+    assert_eq!(grid.get(IVec3::new(-11, 0, -1)), Some(7));
+    assert_eq!(grid.iter().count(), 2);
+    assert_eq!(grid.get(IVec3::new(-2, 0, -1)), None);
+}
+
+/// A bar three voxels long along MagicaVoxel's x, turned a quarter around its vertical axis
+/// by `_r` and placed five voxels up.
+fn rotated_bar_file() -> DotVoxData {
+    // Rows (0, -1, 0), (1, 0, 0), (0, 0, 1): the first row's one is in column 1 and negative,
+    // the second row's in column 0.
+    let quarter_turn = 0b001_0001;
+    vox_file(
+        vec![model(
+            [3, 1, 1],
+            &[[0, 0, 0, 2], [1, 0, 0, 2], [2, 0, 0, 2]],
+        )],
+        vec![
+            transform_node(1, None, 0, "0 0 0"),
+            SceneNode::Group {
+                attributes: Dict::new(),
+                children: vec![2],
+            },
+            SceneNode::Transform {
+                attributes: Dict::new(),
+                frames: vec![Frame::new(Dict::from([
+                    ("_t".to_string(), "0 0 5".to_string()),
+                    ("_r".to_string(), format!("{quarter_turn}")),
+                ]))],
+                child: 3,
+                layer_id: 0,
+            },
+            shape_node(0),
+        ],
+        Vec::new(),
+    )
+}
+
+#[test]
+fn node_rotation_turns_the_model() {
+    let grid = VoxelGrid::from_vox(&rotated_bar_file());
+
+    let cells: Vec<_> = grid.iter().map(|(cell, _)| cell).collect();
+    assert_eq!(cells.len(), 3);
+    // Turned about the vertical axis, the bar runs along z at the height it was placed.
+    assert!(cells.iter().all(|cell| cell.x == cells[0].x && cell.y == 5));
+    assert_eq!(grid.size, UVec3::new(1, 1, 3));
+}
+
+#[test]
+fn colliders_follow_the_node_rotation() {
+    let mut harness = Harness::new();
+    let bar = harness
+        .spawn_voxel_grid(VoxelGrid::from_vox(&rotated_bar_file()))
+        .id();
+    harness.step(1);
+
+    let part = harness
+        .app
+        .world()
+        .get::<Children>(bar)
+        .expect("collider part")[0];
+    let collider = harness.app.world().get::<Collider>(part).unwrap();
+    let compound = collider.as_compound().unwrap();
+    let shapes: Vec<_> = compound.shapes().collect();
+    assert_eq!(shapes.len(), 1);
+    let ColliderView::Cuboid(cuboid) = shapes[0].2 else {
+        panic!("expected a cuboid");
+    };
+    assert_eq!(cuboid.half_extents(), Vec3::new(0.5, 0.5, 1.5));
+}
+
+/// A column three voxels tall with colour 2 at the bottom and 3 at the top, placed five voxels
+/// up by a frame with the given `_r`.
+fn column_file(orientation: u8) -> DotVoxData {
+    vox_file(
+        vec![model([1, 1, 3], &[[0, 0, 0, 2], [0, 0, 2, 3]])],
+        vec![
+            transform_node(1, None, 0, "0 0 0"),
+            SceneNode::Group {
+                attributes: Dict::new(),
+                children: vec![2],
+            },
+            SceneNode::Transform {
+                attributes: Dict::new(),
+                frames: vec![Frame::new(Dict::from([
+                    ("_t".to_string(), "0 0 5".to_string()),
+                    ("_r".to_string(), format!("{orientation}")),
+                ]))],
+                child: 3,
+                layer_id: 0,
+            },
+            shape_node(0),
+        ],
+        Vec::new(),
+    )
+}
+
+fn height_of(grid: &VoxelGrid, index: u8) -> i32 {
+    grid.iter()
+        .find(|&(_, voxel)| voxel == index)
+        .expect("voxel of that colour")
+        .0
+        .y
+}
+
+#[test]
+fn node_flip_mirrors_the_model_along_its_axis() {
+    // Identity rows with the sign bit of the third row set: MagicaVoxel's z is flipped.
+    let flipped = VoxelGrid::from_vox(&column_file(0b100_0100));
+    let upright = VoxelGrid::from_vox(&column_file(0b000_0100));
+
+    assert!(height_of(&upright, 2) < height_of(&upright, 3));
+    assert!(height_of(&flipped, 2) > height_of(&flipped, 3));
+    // Flipping the column doesn't tip it over.
+    let cells: Vec<_> = flipped.iter().map(|(cell, _)| cell).collect();
+    assert!(cells
+        .iter()
+        .all(|cell| cell.x == cells[0].x && cell.z == cells[0].z));
+    assert_eq!(flipped.size, UVec3::new(1, 3, 1));
+}
+
+#[test]
+fn models_without_a_scene_graph_sit_at_the_origin() {
+    let grid = VoxelGrid::from_vox(&vox_file(
+        vec![model([2, 2, 2], &[[1, 0, 1, 7]])],
+        Vec::new(),
+        Vec::new(),
+    ));
+
+    assert_eq!(
+        grid.iter().collect::<Vec<_>>(),
+        vec![(IVec3::new(-1, 0, -1), 7)]
+    );
+}
+
+#[test]
+fn grid_iterates_the_voxels_it_was_built_from() {
+    let voxels = vec![
+        (IVec3::new(-3, 0, 2), 1),
+        (IVec3::new(4, 1, -5), 2),
+        (IVec3::new(0, 7, 0), 255),
+    ];
+    let grid = VoxelGrid::from_voxels(voxels.clone());
+
+    let mut cells: Vec<_> = grid.iter().collect();
+    cells.sort_by_key(|(_, index)| *index);
+    assert_eq!(cells, voxels);
+    assert_eq!(grid.origin, IVec3::new(-3, 0, -5));
+    assert_eq!(grid.size, UVec3::new(8, 8, 8));
+}
+
+#[test]
+fn zone_models_are_kept_out_of_the_grid() {
+    let grid = VoxelGrid::from_vox(&zone_file());
+    assert_eq!(
+        grid.iter().collect::<Vec<_>>(),
+        vec![(IVec3::new(-1, 0, 0), 4)]
+    );
+    assert_eq!(
+        grid.zones.keys().map(String::as_str).collect::<Vec<_>>(),
+        vec!["goal", "water"]
+    );
+    assert_eq!(grid.zones["goal"].get(IVec3::new(-6, 0, 0)), Some(4));
+    assert_eq!(grid.zones["water"].get(IVec3::new(-1, 5, 0)), Some(4));
+}
+
+#[test]
+fn greedy_boxes_merge_a_slab_into_one() {
+    let grid = VoxelGrid::from_voxels(
+        (0..10).flat_map(|x| (0..6).map(move |z| (IVec3::new(x, 3, z), 1 + (x % 2) as u8))),
+    );
+
+    let boxes = grid.greedy_boxes(|_| ());
+    assert_eq!(boxes.len(), 1);
+    assert_eq!(boxes[0].min, IVec3::new(0, 3, 0));
+    assert_eq!(boxes[0].size, UVec3::new(10, 1, 6));
+
+    // Keyed by palette index, alternating columns can't merge along x.
+    assert_eq!(grid.greedy_boxes(|index| index).len(), 10);
+}
+
+#[test]
+fn greedy_boxes_cover_every_voxel_exactly_once() {
+    // An irregular shape: a staircase with a hole, on top of a floor with two materials.
+    let mut voxels = Vec::new();
+    for x in -8..8 {
+        for z in -5..5 {
+            voxels.push((IVec3::new(x, 0, z), if z < 0 { 1 } else { 2 }));
+            for y in 1..(x + 8) / 3 {
+                if (x, z) != (2, 1) {
+                    voxels.push((IVec3::new(x, y, z), 3));
+                }
+            }
+        }
+    }
+    let grid = VoxelGrid::from_voxels(voxels.clone());
+    let boxes = grid.greedy_boxes(|index| index);
+
+    let volume: u32 = boxes
+        .iter()
+        .map(|voxel_box| voxel_box.size.element_product())
+        .sum();
+    assert_eq!(volume as usize, voxels.len());
+    for (cell, index) in voxels {
+        let covering: Vec<_> = boxes
+            .iter()
+            .filter(|voxel_box| {
+                cell.cmpge(voxel_box.min).all()
+                    && cell.cmplt(voxel_box.min + voxel_box.size.as_ivec3()).all()
+            })
+            .collect();
+        assert_eq!(covering.len(), 1, "{cell} covered by {covering:?}");
+        assert_eq!(covering[0].key, index);
+    }
+    assert!(boxes.len() < 40, "{} boxes", boxes.len());
+}
+
+#[test]
+fn player_walks_steadily_across_a_collider_built_from_voxels() {
+    let mut harness = Harness::new();
+    // A floor of 4 x 4 voxel tiles in alternating colours, seams included.
+    let grid = VoxelGrid::from_voxels((-60..60).flat_map(|x| {
+        (-60..60).map(move |z| {
+            (
+                IVec3::new(x, -1, z),
+                1 + ((x / 4 + z / 4) % 2).unsigned_abs() as u8,
+            )
+        })
+    }));
+    let floor = harness.spawn_voxel_grid(grid).id();
+    harness.step(1);
+    let part = harness
+        .app
+        .world()
+        .get::<Children>(floor)
+        .expect("collider part")[0];
+    assert!(harness.app.world().get::<Collider>(part).is_some());
+
+    let forward = forward();
+    harness
+        .spawn_player(-forward * 2.0 + Vec3::Y * 0.3)
+        .settle(300)
+        .press(KeyCode::KeyW);
+    for _ in 0..60 {
+        harness.step(1);
+        let state = harness.grounded_state();
+        assert!(state.is_grounded);
+        assert_eq!(state.ground_entity, Some(part));
+        assert!(state.ground_normal.abs_diff_eq(Vec3::Y, 1e-4));
+        assert!(harness.player_velocity().y.abs() < 1e-3);
+    }
+    assert!(harness.player_position().dot(forward) > -1.0);
+}
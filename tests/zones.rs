@@ -0,0 +1,168 @@
+mod common;
+
+use bevy::{ecs::event::EventCursor, prelude::*};
+use bevy_rapier3d::prelude::*;
+use bevy_vox_scene::{
+    create_voxel_context, create_voxel_scene, VoxLoaderSettings, Voxel, VoxelContext, VoxelData,
+    VoxelModel, VoxelModelInstance, VoxelPalette, VoxelQueryable,
+};
+use common::{forward, Harness, VOXEL_SIZE};
+use voxel_sampo::{
+    config::Config,
+    voxel::VoxelGrid,
+    zone::{PlayerEnteredZone, PlayerExitedZone, Zone},
+};
+
+/// Palette index reserved for the `pool` zone.
+const POOL: u8 = 200;
+
+/// A voxel floor with its top at `y = 0`, a `pool` zone of reserved voxels standing on it
+/// one metre ahead of the origin, and a `goal` zone model two metres ahead.
+fn harness_with_zones() -> Harness {
+    let mut config = Config::default();
+    config.zones.palette.insert(POOL, "pool".to_string());
+    let mut harness = Harness::with_config(config);
+
+    let ahead = |cell: IVec3, from: f32, to: f32| {
+        let distance = (cell.as_vec3() + 0.5).dot(forward()) * VOXEL_SIZE;
+        (from..to).contains(&distance)
+    };
+    let mut grid = VoxelGrid::from_voxels((-80..80).flat_map(|x| {
+        (-80..80).flat_map(move |z| {
+            let floor = (IVec3::new(x, -1, z), 1);
+            let pool = (0..20)
+                .map(move |y| IVec3::new(x, y, z))
+                .filter(move |cell| ahead(*cell, 0.9, 1.1))
+                .map(|cell| (cell, POOL));
+            std::iter::once(floor).chain(pool)
+        })
+    }));
+    let goal = (-80..80)
+        .flat_map(|x| (0..20).flat_map(move |y| (-80..80).map(move |z| IVec3::new(x, y, z))))
+        .filter(|cell| ahead(*cell, 1.9, 2.1))
+        .map(|cell| (cell, 1));
+    grid.zones
+        .insert("goal".to_string(), VoxelGrid::from_voxels(goal));
+
+    harness.spawn_voxel_grid(grid);
+    harness
+}
+
+#[test]
+fn zones_become_sensors_apart_from_the_floor() {
+    let mut harness = harness_with_zones();
+    harness.step(1);
+
+    let mut query = harness
+        .app
+        .world_mut()
+        .query_filtered::<&Zone, With<Sensor>>();
+    let mut zones: Vec<_> = query
+        .iter(harness.app.world())
+        .map(|zone| zone.name.clone())
+        .collect();
+    zones.sort();
+    assert_eq!(zones, vec!["goal", "pool"]);
+
+    // The floor alone is solid: the player stands on it, not on top of the zones.
+    harness.spawn_player(forward() + Vec3::Y * 0.3).settle(300);
+    assert!(harness.player_position().y < 0.3);
+}
+
+#[test]
+fn walking_through_zones_reports_entering_and_leaving_them() {
+    let mut harness = harness_with_zones();
+    harness
+        .spawn_player(Vec3::Y * 0.3)
+        .settle(300)
+        .press(KeyCode::KeyW);
+
+    let mut entered_cursor = EventCursor::<PlayerEnteredZone>::default();
+    let mut exited_cursor = EventCursor::<PlayerExitedZone>::default();
+    let mut log = Vec::new();
+    for _ in 0..90 {
+        harness.step(1);
+        let world = harness.app.world();
+        let entered = world.resource::<Events<PlayerEnteredZone>>();
+        log.extend(
+            entered_cursor
+                .read(entered)
+                .map(|event| format!("enter {}", event.zone)),
+        );
+        let exited = world.resource::<Events<PlayerExitedZone>>();
+        log.extend(
+            exited_cursor
+                .read(exited)
+                .map(|event| format!("exit {}", event.zone)),
+        );
+    }
+
+    assert!(harness.player_position().dot(forward()) > 2.5);
+    assert_eq!(
+        log,
+        vec!["enter pool", "exit pool", "enter goal", "exit goal"]
+    );
+}
+
+#[test]
+fn zone_models_are_hidden() {
+    let mut harness = Harness::new();
+    let zone = harness
+        .app
+        .world_mut()
+        .spawn((Name::new("props/zone:exit"), Visibility::Inherited))
+        .id();
+    let wall = harness
+        .app
+        .world_mut()
+        .spawn((Name::new("props/wall"), Visibility::Inherited))
+        .id();
+    harness.step(1);
+
+    let world = harness.app.world();
+    assert_eq!(world.get::<Visibility>(zone), Some(&Visibility::Hidden));
+    assert_eq!(world.get::<Visibility>(wall), Some(&Visibility::Inherited));
+}
+
+#[test]
+fn reserved_palette_voxels_are_not_drawn() {
+    let mut config = Config::default();
+    config.zones.palette.insert(POOL, "pool".to_string());
+    let mut harness = Harness::with_config(config);
+    harness
+        .app
+        .init_asset::<Mesh>()
+        .init_asset::<Image>()
+        .init_asset::<Scene>()
+        .init_asset::<VoxelModel>()
+        .init_asset::<VoxelContext>();
+
+    // A rendered model of one floor voxel and one pool voxel, as `bevy_vox_scene` spawns it.
+    let world = harness.app.world_mut();
+    let palette = VoxelPalette::from_colors(vec![Color::WHITE; 255], false);
+    let context = world
+        .run_system_cached_with(create_voxel_context, palette)
+        .unwrap();
+    let mut data = VoxelData::new(UVec3::new(2, 1, 1), VoxLoaderSettings::default());
+    data.set_voxel(Voxel(1), UVec3::ZERO);
+    data.set_voxel(Voxel(POOL), UVec3::X);
+    let scene = world
+        .run_system_cached_with(create_voxel_scene, (data, "pool".to_string(), context))
+        .unwrap();
+    let mut scene = world
+        .resource_mut::<Assets<Scene>>()
+        .remove(&scene)
+        .unwrap();
+    let (instance, mesh) = scene
+        .world
+        .query::<(&VoxelModelInstance, &Mesh3d)>()
+        .single(&scene.world)
+        .unwrap();
+    world.spawn((instance.clone(), mesh.clone()));
+    harness.step(1);
+
+    let models = harness.app.world().resource::<Assets<VoxelModel>>();
+    let model = models.get(&instance.model).unwrap();
+    assert_eq!(model.get_voxel_at_point(IVec3::ZERO), Ok(Voxel(1)));
+    assert_eq!(model.get_voxel_at_point(IVec3::X), Ok(Voxel::EMPTY));
+}